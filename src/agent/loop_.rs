@@ -3006,7 +3006,9 @@ pub(crate) async fn run_tool_call_loop(
                     mgr.record_decision(&tool_name, &tool_args, decision, channel_name);
 
                     if decision == ApprovalResponse::No {
-                        let denied = "Denied by user.".to_string();
+                        let denied = mgr
+                            .messages()
+                            .render(crate::i18n::MessageId::ApprovalDenied, &[]);
                         runtime_trace::record_event(
                             "tool_call_result",
                             Some(channel_name),
@@ -3673,6 +3675,7 @@ pub async fn run(
         .unwrap_or_else(crate::i18n::detect_locale);
     let i18n_search_dirs = crate::i18n::default_search_dirs(&config.workspace_dir);
     let i18n_descs = crate::i18n::ToolDescriptions::load(&i18n_locale, &i18n_search_dirs);
+    let i18n_messages = crate::i18n::MessageCatalog::load(&i18n_locale, &i18n_search_dirs);
 
     // ── Build system prompt from workspace MD files (OpenClaw framework) ──
     let skills = crate::skills::load_skills_with_config(&config.workspace_dir, &config);
@@ -3831,7 +3834,7 @@ pub async fn run(
 
     // ── Approval manager (supervised mode) ───────────────────────
     let approval_manager = if interactive {
-        Some(ApprovalManager::from_config(&config.autonomy))
+        Some(ApprovalManager::from_config(&config.autonomy).with_messages(i18n_messages))
     } else {
         None
     };
@@ -4,7 +4,9 @@
 //! with session-scoped "Always" allowlists and audit logging.
 
 use crate::config::AutonomyConfig;
+use crate::i18n::{MessageCatalog, MessageId};
 use crate::security::AutonomyLevel;
+use crate::tools::url_validation::redact_url_fragments;
 use chrono::Utc;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
@@ -70,6 +72,8 @@ pub struct ApprovalManager {
     session_allowlist: Mutex<HashSet<String>>,
     /// Audit trail of approval decisions.
     audit_log: Mutex<Vec<ApprovalLogEntry>>,
+    /// Operator-facing prompt templates.
+    messages: MessageCatalog,
 }
 
 impl ApprovalManager {
@@ -82,6 +86,7 @@ impl ApprovalManager {
             non_interactive: false,
             session_allowlist: Mutex::new(HashSet::new()),
             audit_log: Mutex::new(Vec::new()),
+            messages: MessageCatalog::english(),
         }
     }
 
@@ -98,9 +103,22 @@ impl ApprovalManager {
             non_interactive: true,
             session_allowlist: Mutex::new(HashSet::new()),
             audit_log: Mutex::new(Vec::new()),
+            messages: MessageCatalog::english(),
         }
     }
 
+    /// Render operator prompts through a localized message catalog.
+    #[must_use]
+    pub fn with_messages(mut self, messages: MessageCatalog) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    /// The catalog operator-facing approval messages are rendered through.
+    pub fn messages(&self) -> &MessageCatalog {
+        &self.messages
+    }
+
     /// Returns `true` when this manager operates in non-interactive mode
     /// (i.e. for channel-driven runs where no operator can approve).
     pub fn is_non_interactive(&self) -> bool {
@@ -164,8 +182,9 @@ impl ApprovalManager {
             allowlist.insert(tool_name.to_string());
         }
 
-        // Append to audit log.
-        let summary = summarize_args(args);
+        // Append to audit log. URL fragments may hold tokens, so they never
+        // reach the trail.
+        let summary = redact_url_fragments(&summarize_args(args)).into_owned();
         let entry = ApprovalLogEntry {
             timestamp: Utc::now().to_rfc3339(),
             tool_name: tool_name.to_string(),
@@ -192,19 +211,32 @@ impl ApprovalManager {
     /// Only called for interactive (CLI) managers. Non-interactive managers
     /// auto-deny in the tool-call loop before reaching this point.
     pub fn prompt_cli(&self, request: &ApprovalRequest) -> ApprovalResponse {
-        prompt_cli_interactive(request)
+        prompt_cli_interactive(request, &self.messages)
     }
 }
 
 // ── CLI prompt ───────────────────────────────────────────────────
 
 /// Display the approval prompt and read user input from stdin.
-fn prompt_cli_interactive(request: &ApprovalRequest) -> ApprovalResponse {
+fn prompt_cli_interactive(
+    request: &ApprovalRequest,
+    messages: &MessageCatalog,
+) -> ApprovalResponse {
     let summary = summarize_args(&request.arguments);
+    let tool = request.tool_name.as_str();
     eprintln!();
-    eprintln!("🔧 Agent wants to execute: {}", request.tool_name);
-    eprintln!("   {summary}");
-    eprint!("   [Y]es / [N]o / [A]lways for {}: ", request.tool_name);
+    eprintln!(
+        "{}",
+        messages.render(MessageId::ApprovalRequested, &[("tool", tool)])
+    );
+    eprintln!(
+        "{}",
+        messages.render(MessageId::ApprovalArguments, &[("summary", &summary)])
+    );
+    eprint!(
+        "{}",
+        messages.render(MessageId::ApprovalChoices, &[("tool", tool)])
+    );
     let _ = io::stderr().flush();
 
     let stdin = io::stdin();
@@ -213,10 +245,29 @@ fn prompt_cli_interactive(request: &ApprovalRequest) -> ApprovalResponse {
         return ApprovalResponse::No;
     }
 
-    match line.trim().to_ascii_lowercase().as_str() {
-        "y" | "yes" => ApprovalResponse::Yes,
-        "a" | "always" => ApprovalResponse::Always,
-        _ => ApprovalResponse::No,
+    parse_reply(&line, messages)
+}
+
+/// Map a reply to the choice line. The English answers always work; a
+/// translation adds its own through `approval.reply_yes` and
+/// `approval.reply_always`. Anything else is a denial.
+fn parse_reply(line: &str, messages: &MessageCatalog) -> ApprovalResponse {
+    let reply = line.trim().to_lowercase();
+    let accepts = |id: MessageId| {
+        let translated = messages.render(id, &[]);
+        !reply.is_empty()
+            && id
+                .english()
+                .split(',')
+                .chain(translated.split(','))
+                .any(|answer| answer.trim().to_lowercase() == reply)
+    };
+    if accepts(MessageId::ApprovalReplyYes) {
+        ApprovalResponse::Yes
+    } else if accepts(MessageId::ApprovalReplyAlways) {
+        ApprovalResponse::Always
+    } else {
+        ApprovalResponse::No
     }
 }
 
@@ -408,6 +459,21 @@ mod tests {
         assert_eq!(log[0].channel, "telegram");
     }
 
+    #[test]
+    fn audit_log_redacts_url_fragments() {
+        let mgr = ApprovalManager::from_config(&supervised_config());
+        mgr.record_decision(
+            "browser_open",
+            &serde_json::json!({"url": "https://app.example.com/cb#access_token=SECRET123"}),
+            ApprovalResponse::Yes,
+            "cli",
+        );
+
+        let summary = &mgr.audit_log()[0].arguments_summary;
+        assert!(!summary.contains("SECRET123"), "{summary}");
+        assert!(summary.contains("https://app.example.com/cb#[redacted]"));
+    }
+
     // ── summarize_args ───────────────────────────────────────
 
     #[test]
@@ -540,6 +606,42 @@ mod tests {
         assert!(mgr.needs_approval("shell"));
     }
 
+    // ── reply parsing ────────────────────────────────────────
+
+    #[test]
+    fn english_replies_are_parsed() {
+        let messages = MessageCatalog::english();
+        assert_eq!(parse_reply("Y\n", &messages), ApprovalResponse::Yes);
+        assert_eq!(parse_reply(" yes ", &messages), ApprovalResponse::Yes);
+        assert_eq!(parse_reply("a", &messages), ApprovalResponse::Always);
+        assert_eq!(parse_reply("ALWAYS", &messages), ApprovalResponse::Always);
+        assert_eq!(parse_reply("", &messages), ApprovalResponse::No);
+        assert_eq!(parse_reply("sure", &messages), ApprovalResponse::No);
+    }
+
+    #[test]
+    fn translated_replies_are_parsed_alongside_english() {
+        let messages = MessageCatalog::from_toml(
+            "de",
+            r#"[messages]
+"approval.reply_yes" = "j, ja"
+"approval.reply_always" = "i, immer"
+"approval.denied" = "Vom Benutzer abgelehnt."
+"#,
+        )
+        .unwrap();
+        assert_eq!(parse_reply("JA", &messages), ApprovalResponse::Yes);
+        assert_eq!(parse_reply("immer", &messages), ApprovalResponse::Always);
+        assert_eq!(parse_reply("yes", &messages), ApprovalResponse::Yes);
+        assert_eq!(parse_reply("nein", &messages), ApprovalResponse::No);
+
+        let mgr = ApprovalManager::from_config(&supervised_config()).with_messages(messages);
+        assert_eq!(
+            mgr.messages().render(MessageId::ApprovalDenied, &[]),
+            "Vom Benutzer abgelehnt."
+        );
+    }
+
     // ── ApprovalResponse serde ───────────────────────────────
 
     #[test]
@@ -1,6 +1,7 @@
 //! Link enricher: auto-detects URLs in inbound messages, fetches their content,
 //! and prepends summaries so the agent has link context without explicit tool calls.
 
+use crate::tools::url_validation::{HostKey, NumericHost, classify_numeric_host, host_key_matches};
 use regex::Regex;
 use std::net::IpAddr;
 use std::sync::LazyLock;
@@ -47,30 +48,28 @@ pub fn extract_urls(text: &str, max: usize) -> Vec<String> {
 /// Returns `true` if the URL points to a private/local address that should be
 /// blocked for SSRF protection.
 pub fn is_ssrf_target(url: &str) -> bool {
-    let host = match extract_host(url) {
-        Some(h) => h,
-        None => return true, // unparseable URLs are rejected
+    let Some(host) = extract_host(url) else {
+        return true; // unparseable URLs are rejected
     };
 
     // Check hostname-based locals
-    if host == "localhost"
-        || host.ends_with(".localhost")
-        || host.ends_with(".local")
-        || host == "local"
-    {
+    let local_domains = [HostKey::from("localhost"), HostKey::from("local")];
+    if host_key_matches(&host, &local_domains) {
         return true;
     }
 
     // Check IP-based private ranges
-    if let Ok(ip) = host.parse::<IpAddr>() {
+    if let Ok(ip) = host.as_str().parse::<IpAddr>() {
         return is_private_ip(ip);
     }
 
-    false
+    // HostKey already decoded valid legacy IPv4 spellings; numeric hosts
+    // left over don't decode and must not reach the resolver.
+    classify_numeric_host(host.as_str()) == NumericHost::Invalid
 }
 
 /// Extract the host portion from a URL string.
-fn extract_host(url: &str) -> Option<String> {
+fn extract_host(url: &str) -> Option<HostKey> {
     let rest = url
         .strip_prefix("https://")
         .or_else(|| url.strip_prefix("http://"))?;
@@ -85,7 +84,7 @@ fn extract_host(url: &str) -> Option<String> {
     } else {
         authority.split(':').next().unwrap_or(authority)
     };
-    Some(host.to_lowercase())
+    Some(HostKey::from(host))
 }
 
 /// Check if an IP address falls within private/reserved ranges.
@@ -345,6 +344,22 @@ mod tests {
         assert!(is_ssrf_target("http://myhost.local/api"));
     }
 
+    #[test]
+    fn ssrf_blocks_trailing_dot_and_mixed_case_hosts() {
+        assert!(is_ssrf_target("http://LOCALHOST./admin"));
+        assert!(is_ssrf_target("http://myhost.local./api"));
+        assert!(is_ssrf_target("http://127.0.0.1./secret"));
+    }
+
+    #[test]
+    fn ssrf_blocks_alternate_ipv4_notations() {
+        assert!(is_ssrf_target("http://2130706433/"));
+        assert!(is_ssrf_target("http://0x7f.0.0.1/"));
+        assert!(is_ssrf_target("http://0177.0.0.1/"));
+        assert!(is_ssrf_target("http://08.0.0.1/"));
+        assert!(!is_ssrf_target("http://0x08080808/"));
+    }
+
     #[test]
     fn ssrf_allows_public_urls() {
         assert!(!is_ssrf_target("https://example.com/page"));
@@ -1,4 +1,5 @@
 use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::tools::url_validation::HostKey;
 use anyhow::Context;
 use async_trait::async_trait;
 use base64::Engine as _;
@@ -901,8 +902,7 @@ impl SlackChannel {
 
     fn parse_slack_permalink(raw_url: &str) -> Option<SlackPermalinkRef> {
         let url = reqwest::Url::parse(raw_url).ok()?;
-        let host = url.host_str()?.trim_end_matches('.').to_ascii_lowercase();
-        if host != "slack.com" && !host.ends_with(".slack.com") {
+        if !HostKey::from(url.host_str()?).is_within(&HostKey::from("slack.com")) {
             return None;
         }
 
@@ -1380,14 +1380,10 @@ impl SlackChannel {
     }
 
     fn is_allowed_slack_media_hostname(host: &str) -> bool {
-        let normalized = host.trim().trim_end_matches('.').to_ascii_lowercase();
-        if normalized.is_empty() {
-            return false;
-        }
-
+        let host = HostKey::from(host);
         SLACK_ALLOWED_MEDIA_HOST_SUFFIXES
             .iter()
-            .any(|suffix| normalized == *suffix || normalized.ends_with(&format!(".{suffix}")))
+            .any(|suffix| host.is_within(&HostKey::from(*suffix)))
     }
 
     fn redact_slack_url(url: &reqwest::Url) -> String {
@@ -1,4 +1,5 @@
 use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::tools::url_validation::HostKey;
 use async_trait::async_trait;
 use uuid::Uuid;
 
@@ -217,12 +218,12 @@ impl WatiChannel {
         messages
     }
 
-    /// Extract host from URL string.
-    fn extract_host(url_str: &str) -> Option<String> {
+    /// Extract the canonical host from a URL string.
+    fn extract_host(url_str: &str) -> Option<HostKey> {
         reqwest::Url::parse(url_str)
             .ok()?
             .host_str()
-            .map(|h| h.to_ascii_lowercase())
+            .map(HostKey::from)
     }
 
     /// Attempt to download and transcribe an audio message from a WATI webhook payload.
@@ -1021,13 +1022,21 @@ mod tests {
     fn extract_host_uses_url_parser() {
         assert_eq!(
             WatiChannel::extract_host("https://live-mt-server.wati.io/media/123"),
-            Some("live-mt-server.wati.io".to_string())
+            Some(HostKey::from("live-mt-server.wati.io"))
         );
         // URL with userinfo@ — proper parser extracts the real host, not the
         // attacker-controlled host that naive string splitting would produce
         assert_eq!(
             WatiChannel::extract_host("https://live-mt-server.wati.io@evil.com/media/123"),
-            Some("evil.com".to_string())
+            Some(HostKey::from("evil.com"))
+        );
+    }
+
+    #[test]
+    fn extract_host_is_canonical_for_comparison() {
+        assert_eq!(
+            WatiChannel::extract_host("https://LIVE-MT-SERVER.wati.io./media/123"),
+            WatiChannel::extract_host("https://live-mt-server.wati.io/v1")
         );
     }
 
@@ -390,11 +390,12 @@ pub struct Config {
     #[serde(default)]
     pub plugins: PluginsConfig,
 
-    /// Locale for tool descriptions (e.g. `"en"`, `"zh-CN"`).
+    /// Locale for tool descriptions and operator messages (e.g. `"en"`, `"zh-CN"`).
     ///
     /// When set, tool descriptions shown in system prompts are loaded from
     /// `tool_descriptions/<locale>.toml`. Falls back to English, then to
-    /// hardcoded descriptions.
+    /// hardcoded descriptions. Operator-facing prompts (e.g. CLI approval)
+    /// are loaded from `messages/<locale>.toml`, which is validated at startup.
     ///
     /// If omitted or empty, the locale is auto-detected from `ZEROCLAW_LOCALE`,
     /// `LANG`, or `LC_ALL` environment variables (defaulting to `"en"`).
@@ -2520,6 +2521,23 @@ pub struct HttpRequestConfig {
     /// Default: false (deny private hosts for SSRF protection).
     #[serde(default)]
     pub allow_private_hosts: bool,
+    /// Response content types accepted per domain, e.g.
+    /// `"api.bank.example" = ["application/json"]`. Keys match like
+    /// `allowed_domains` and the most specific key wins. Bodies of any other
+    /// type (declared or sniffed) are withheld from the model. Domains
+    /// without an entry accept every type (default: empty).
+    #[serde(default)]
+    pub expected_content_types: HashMap<String, Vec<String>>,
+    /// Also allow the public hosts referenced by the workspace's `.git/config`,
+    /// `Cargo.toml` and `package.json` (default: false)
+    #[serde(default)]
+    pub trust_project_origins: bool,
+    /// Domains whose requests use `Title-Case` header names instead of
+    /// lowercase, for endpoints that reject non-browser casing (exact or
+    /// subdomain match; default: empty). The casing a name was written with
+    /// is never sent as-is; it is always one of these two forms.
+    #[serde(default)]
+    pub browser_like_casing_domains: Vec<String>,
 }
 
 impl Default for HttpRequestConfig {
@@ -2530,6 +2548,9 @@ impl Default for HttpRequestConfig {
             max_response_size: default_http_max_response_size(),
             timeout_secs: default_http_timeout_secs(),
             allow_private_hosts: false,
+            expected_content_types: HashMap::new(),
+            trust_project_origins: false,
+            browser_like_casing_domains: Vec::new(),
         }
     }
 }
@@ -2570,9 +2591,22 @@ pub struct WebFetchConfig {
     /// Request timeout in seconds (default: 30)
     #[serde(default = "default_web_fetch_timeout_secs")]
     pub timeout_secs: u64,
+    /// Maximum redirect hops to follow; every hop is re-validated (default: 5)
+    #[serde(default = "default_web_fetch_max_redirects")]
+    pub max_redirects: usize,
     /// Firecrawl fallback configuration (`[web_fetch.firecrawl]`)
     #[serde(default)]
     pub firecrawl: FirecrawlConfig,
+    /// Also allow the public hosts referenced by the workspace's `.git/config`,
+    /// `Cargo.toml` and `package.json` (default: false)
+    #[serde(default)]
+    pub trust_project_origins: bool,
+    /// Domains whose requests use `Title-Case` header names instead of
+    /// lowercase, for endpoints that reject non-browser casing (exact or
+    /// subdomain match; default: empty). The casing a name was written with
+    /// is never sent as-is; it is always one of these two forms.
+    #[serde(default)]
+    pub browser_like_casing_domains: Vec<String>,
 }
 
 /// Firecrawl fallback mode: scrape a single page or crawl linked pages.
@@ -2635,6 +2669,10 @@ fn default_web_fetch_timeout_secs() -> u64 {
     30
 }
 
+fn default_web_fetch_max_redirects() -> usize {
+    5
+}
+
 fn default_web_fetch_allowed_domains() -> Vec<String> {
     vec!["*".into()]
 }
@@ -2648,7 +2686,10 @@ impl Default for WebFetchConfig {
             allowed_private_hosts: vec![],
             max_response_size: default_web_fetch_max_response_size(),
             timeout_secs: default_web_fetch_timeout_secs(),
+            max_redirects: default_web_fetch_max_redirects(),
             firecrawl: FirecrawlConfig::default(),
+            trust_project_origins: false,
+            browser_like_casing_domains: Vec::new(),
         }
     }
 }
@@ -7538,6 +7579,7 @@ impl Default for ResourceLimitsConfig {
 }
 
 /// Audit logging configuration
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct AuditConfig {
     /// Enable audit logging
@@ -7552,9 +7594,27 @@ pub struct AuditConfig {
     #[serde(default = "default_audit_max_size_mb")]
     pub max_size_mb: u32,
 
+    /// Number of rotated files (`audit.log.1.log`, `.2.log`, ...) to keep
+    #[serde(default = "default_audit_max_rotated_files")]
+    pub max_rotated_files: u32,
+
+    /// Delete rotated files older than this many days (0 = keep regardless of age)
+    #[serde(default)]
+    pub max_age_days: u32,
+
+    /// Gzip rotated files once they shift past `.1.log` (`audit.log.2.log.gz`, ...)
+    #[serde(default)]
+    pub compress_rotated: bool,
+
     /// Sign events with HMAC for tamper evidence
     #[serde(default)]
     pub sign_events: bool,
+
+    /// Debug override: record URL fragments verbatim. Off by default because
+    /// SPA routes carry secrets there (e.g. `#access_token=`); fragments are
+    /// otherwise replaced with a redaction marker.
+    #[serde(default)]
+    pub log_fragments: bool,
 }
 
 fn default_audit_enabled() -> bool {
@@ -7569,13 +7629,21 @@ fn default_audit_max_size_mb() -> u32 {
     100
 }
 
+fn default_audit_max_rotated_files() -> u32 {
+    10
+}
+
 impl Default for AuditConfig {
     fn default() -> Self {
         Self {
             enabled: default_audit_enabled(),
             log_path: default_audit_log_path(),
             max_size_mb: default_audit_max_size_mb(),
+            max_rotated_files: default_audit_max_rotated_files(),
+            max_age_days: 0,
+            compress_rotated: false,
             sign_events: false,
+            log_fragments: false,
         }
     }
 }
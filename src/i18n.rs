@@ -1,13 +1,21 @@
-//! Internationalization support for tool descriptions.
+//! Internationalization support for tool descriptions and operator messages.
 //!
 //! Loads tool descriptions from TOML locale files in `tool_descriptions/`.
 //! Falls back to English when a locale file or specific key is missing,
 //! and ultimately falls back to the hardcoded `tool.description()` value
 //! if no file-based description exists.
-
-use std::collections::HashMap;
+//!
+//! Operator-facing messages (approval prompts and the like) are identified by
+//! a stable [`MessageId`] and rendered through a [`MessageCatalog`]. English
+//! templates are built in; translations are loaded from `messages/<locale>.toml`
+//! and validated up front, so a bad placeholder is reported at startup (and the
+//! English catalog used instead) rather than surfacing mid-prompt. Model-facing
+//! tool errors are not routed through the catalog.
+
+use anyhow::{Result, bail};
+use std::collections::{BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Container for locale-specific tool descriptions loaded from TOML files.
 #[derive(Debug, Clone)]
@@ -169,9 +177,224 @@ fn load_locale_file(locale: &str, search_dirs: &[PathBuf]) -> HashMap<String, St
     HashMap::new()
 }
 
+// ── Operator message catalog ─────────────────────────────────────
+
+/// Stable identifiers for operator-facing messages.
+///
+/// The key strings are part of the translation file format; never rename one
+/// without keeping the old key working.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[allow(clippy::enum_variant_names)]
+pub enum MessageId {
+    /// Header line of the CLI approval prompt.
+    ApprovalRequested,
+    /// Summary of the tool arguments under the approval header.
+    ApprovalArguments,
+    /// The `[Y]es / [N]o / [A]lways` choice line.
+    ApprovalChoices,
+    /// Comma-separated replies to the choice line that mean "yes".
+    ApprovalReplyYes,
+    /// Comma-separated replies to the choice line that mean "always".
+    ApprovalReplyAlways,
+    /// Result recorded for a tool call the operator denied.
+    ApprovalDenied,
+}
+
+impl MessageId {
+    pub const ALL: &'static [MessageId] = &[
+        MessageId::ApprovalRequested,
+        MessageId::ApprovalArguments,
+        MessageId::ApprovalChoices,
+        MessageId::ApprovalReplyYes,
+        MessageId::ApprovalReplyAlways,
+        MessageId::ApprovalDenied,
+    ];
+
+    /// Key used in `messages/<locale>.toml`.
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::ApprovalRequested => "approval.requested",
+            Self::ApprovalArguments => "approval.arguments",
+            Self::ApprovalChoices => "approval.choices",
+            Self::ApprovalReplyYes => "approval.reply_yes",
+            Self::ApprovalReplyAlways => "approval.reply_always",
+            Self::ApprovalDenied => "approval.denied",
+        }
+    }
+
+    /// Built-in English template.
+    pub fn english(self) -> &'static str {
+        match self {
+            Self::ApprovalRequested => "🔧 Agent wants to execute: {tool}",
+            Self::ApprovalArguments => "   {summary}",
+            Self::ApprovalChoices => "   [Y]es / [N]o / [A]lways for {tool}: ",
+            Self::ApprovalReplyYes => "y, yes",
+            Self::ApprovalReplyAlways => "a, always",
+            Self::ApprovalDenied => "Denied by user.",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|id| id.key() == key)
+    }
+}
+
+/// Renders [`MessageId`]s for the configured locale, falling back to the
+/// built-in English template for keys the translation does not cover.
+#[derive(Debug, Clone)]
+pub struct MessageCatalog {
+    templates: HashMap<MessageId, String>,
+    locale: String,
+}
+
+/// TOML structure: `[messages]` table mapping message key -> template.
+#[derive(Debug, serde::Deserialize)]
+struct MessageFile {
+    #[serde(default)]
+    messages: HashMap<String, String>,
+}
+
+impl MessageCatalog {
+    /// Catalog with only the built-in English templates.
+    pub fn english() -> Self {
+        Self {
+            templates: HashMap::new(),
+            locale: "en".to_string(),
+        }
+    }
+
+    /// Load `messages/<locale>.toml` from the first search dir that has it.
+    ///
+    /// A missing file yields the English catalog. A file that exists but
+    /// fails to parse, names an unknown key, or uses a template whose
+    /// placeholders differ from the English one is logged and ignored, so a
+    /// broken translation never keeps the agent from starting.
+    pub fn load(locale: &str, search_dirs: &[PathBuf]) -> Self {
+        let filename = format!("messages/{locale}.toml");
+        for dir in search_dirs {
+            let path = dir.join(&filename);
+            let Ok(contents) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            match Self::from_toml(locale, &contents) {
+                Ok(catalog) => {
+                    debug!(path = %path.display(), keys = catalog.templates.len(), "loaded message catalog");
+                    return catalog;
+                }
+                Err(e) => {
+                    warn!(
+                        path = %path.display(),
+                        "invalid message catalog, using English: {e}"
+                    );
+                    return Self::english();
+                }
+            }
+        }
+        Self {
+            templates: HashMap::new(),
+            locale: locale.to_string(),
+        }
+    }
+
+    /// Parse and validate a catalog from TOML source.
+    pub fn from_toml(locale: &str, contents: &str) -> Result<Self> {
+        let parsed: MessageFile = toml::from_str(contents)?;
+        let mut templates = HashMap::new();
+        let mut problems = Vec::new();
+
+        let mut keys: Vec<_> = parsed.messages.into_iter().collect();
+        keys.sort();
+        for (key, template) in keys {
+            let Some(id) = MessageId::from_key(&key) else {
+                problems.push(format!("unknown message key '{key}'"));
+                continue;
+            };
+            let expected = placeholders(id.english());
+            let actual = placeholders(&template);
+            for missing in expected.difference(&actual) {
+                problems.push(format!("'{key}' is missing placeholder {{{missing}}}"));
+            }
+            for extra in actual.difference(&expected) {
+                problems.push(format!("'{key}' uses unknown placeholder {{{extra}}}"));
+            }
+            templates.insert(id, template);
+        }
+
+        if !problems.is_empty() {
+            bail!("{}", problems.join("; "));
+        }
+        Ok(Self {
+            templates,
+            locale: locale.to_string(),
+        })
+    }
+
+    /// Render a message, substituting `{name}` placeholders from `params`.
+    ///
+    /// Substitution is a single pass over the template, so braces inside a
+    /// parameter value are copied verbatim rather than expanded again.
+    pub fn render(&self, id: MessageId, params: &[(&str, &str)]) -> String {
+        let template = self.templates.get(&id).map_or(id.english(), String::as_str);
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let value = after.find('}').and_then(|end| {
+                params
+                    .iter()
+                    .find(|(name, _)| *name == &after[..end])
+                    .map(|(_, value)| (end, *value))
+            });
+            match value {
+                Some((end, value)) => {
+                    out.push_str(value);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    out.push('{');
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// The locale this catalog was loaded for.
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+}
+
+impl Default for MessageCatalog {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+/// Names of the `{placeholder}`s in a template.
+fn placeholders(template: &str) -> BTreeSet<&str> {
+    let mut names = BTreeSet::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let after = &rest[start + 1..];
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let name = &after[..end];
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            names.insert(name);
+        }
+        rest = &after[end + 1..];
+    }
+    names
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fmt::Write as _;
     use std::fs;
 
     /// Helper: create a temp dir with a `tool_descriptions/<locale>.toml` file.
@@ -315,4 +538,125 @@ shell = "Einen Shell-Befehl im Arbeitsverzeichnis ausführen"
             Some("Einen Shell-Befehl im Arbeitsverzeichnis ausführen")
         );
     }
+
+    #[test]
+    fn message_catalog_defaults_to_english() {
+        let catalog = MessageCatalog::english();
+        assert_eq!(
+            catalog.render(MessageId::ApprovalRequested, &[("tool", "shell")]),
+            "🔧 Agent wants to execute: shell"
+        );
+        assert_eq!(catalog.locale(), "en");
+    }
+
+    #[test]
+    fn message_catalog_substitutes_translated_templates() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("messages")).unwrap();
+        fs::write(
+            tmp.path().join("messages/de.toml"),
+            r#"[messages]
+"approval.requested" = "🔧 Agent möchte {tool} ausführen"
+"approval.choices" = "   [Y]a / [N]ein / [A]immer für {tool}: "
+"#,
+        )
+        .unwrap();
+
+        let catalog = MessageCatalog::load("de", &[tmp.path().to_path_buf()]);
+        assert_eq!(catalog.locale(), "de");
+        assert_eq!(
+            catalog.render(MessageId::ApprovalRequested, &[("tool", "shell")]),
+            "🔧 Agent möchte shell ausführen"
+        );
+        // Untranslated key falls back to English.
+        assert_eq!(
+            catalog.render(MessageId::ApprovalArguments, &[("summary", "cmd: ls")]),
+            "   cmd: ls"
+        );
+    }
+
+    #[test]
+    fn message_catalog_missing_file_falls_back_to_english() {
+        let tmp = tempfile::tempdir().unwrap();
+        let catalog = MessageCatalog::load("fr", &[tmp.path().to_path_buf()]);
+        assert_eq!(
+            catalog.render(MessageId::ApprovalChoices, &[("tool", "shell")]),
+            "   [Y]es / [N]o / [A]lways for shell: "
+        );
+    }
+
+    #[test]
+    fn message_catalog_invalid_file_falls_back_to_english() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir_all(tmp.path().join("messages")).unwrap();
+        fs::write(
+            tmp.path().join("messages/de.toml"),
+            r#"[messages]
+"approval.requested" = "Agent möchte {command} ausführen"
+"#,
+        )
+        .unwrap();
+
+        let catalog = MessageCatalog::load("de", &[tmp.path().to_path_buf()]);
+        assert_eq!(catalog.locale(), "en");
+        assert_eq!(
+            catalog.render(MessageId::ApprovalRequested, &[("tool", "shell")]),
+            "🔧 Agent wants to execute: shell"
+        );
+    }
+
+    #[test]
+    fn message_catalog_does_not_expand_placeholders_inside_values() {
+        let catalog = MessageCatalog::english();
+        assert_eq!(
+            catalog.render(
+                MessageId::ApprovalArguments,
+                &[("summary", "cmd: echo {summary} {tool}"), ("tool", "shell")]
+            ),
+            "   cmd: echo {summary} {tool}"
+        );
+        assert_eq!(
+            catalog.render(MessageId::ApprovalRequested, &[("tool", "{tool}}")]),
+            "🔧 Agent wants to execute: {tool}}"
+        );
+    }
+
+    #[test]
+    fn message_catalog_rejects_placeholder_mismatches() {
+        let err = MessageCatalog::from_toml(
+            "xx",
+            r#"[messages]
+"approval.requested" = "run {command}"
+"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("missing placeholder {tool}"), "{err}");
+        assert!(err.contains("unknown placeholder {command}"), "{err}");
+    }
+
+    #[test]
+    fn message_catalog_rejects_unknown_keys() {
+        let err = MessageCatalog::from_toml(
+            "xx",
+            r#"[messages]
+"approval.nope" = "{tool}"
+"#,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("unknown message key 'approval.nope'"), "{err}");
+    }
+
+    #[test]
+    fn english_templates_round_trip_through_validation() {
+        let mut toml = String::from("[messages]\n");
+        for id in MessageId::ALL {
+            writeln!(toml, "{:?} = {:?}", id.key(), id.english()).unwrap();
+        }
+        let catalog = MessageCatalog::from_toml("en", &toml).unwrap();
+        for id in MessageId::ALL {
+            assert_eq!(catalog.render(*id, &[]), id.english());
+        }
+    }
 }
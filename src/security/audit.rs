@@ -2,16 +2,23 @@
 //!
 //! Each audit entry is chained via a Merkle hash: `entry_hash = SHA-256(prev_hash || canonical_json)`.
 //! This makes the trail tamper-evident — modifying any entry invalidates all subsequent hashes.
+//!
+//! The active log rotates to `audit.log.1.log` once it exceeds `max_size_mb`; older rotations
+//! shift up by one, and are gzipped (`.2.log.gz`, ...) when `compress_rotated` is set. Rotated files beyond
+//! `max_rotated_files` or older than `max_age_days` are pruned at startup and after every
+//! rotation. [`read_events`] reads the whole retained history across rotated files.
 
 use crate::config::AuditConfig;
+use crate::tools::url_validation::redact_url_fragments;
 use anyhow::{Result, bail};
 use chrono::{DateTime, Utc};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions, TryLockError};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 use uuid::Uuid;
 
 /// Well-known seed for the genesis entry's `prev_hash`.
@@ -252,13 +259,20 @@ impl AuditLogger {
 
         let log_path = zeroclaw_dir.join(&config.log_path);
         let chain_state = recover_chain_state(&log_path);
-        Ok(Self {
+        let logger = Self {
             log_path,
             config,
             buffer: Mutex::new(Vec::new()),
             chain: Mutex::new(chain_state),
             signing_key,
-        })
+        };
+        if logger.config.enabled && !rotated_files(&logger.log_path).is_empty() {
+            // A writer already holding the lock prunes after it rotates.
+            if let Some(_lock) = RotationLock::try_acquire(&lock_path_for(&logger.log_path))? {
+                logger.prune_rotated()?;
+            }
+        }
+        Ok(logger)
     }
 
     /// Compute HMAC-SHA256 signature over entry_hash when sign_events enabled.
@@ -288,6 +302,14 @@ impl AuditLogger {
 
         // Populate chain fields under the lock
         let mut chained = event.clone();
+        if !self.config.log_fragments {
+            if let Some(command) = chained.action.as_mut().and_then(|a| a.command.as_mut()) {
+                *command = redact_url_fragments(command).into_owned();
+            }
+            if let Some(error) = chained.result.as_mut().and_then(|r| r.error.as_mut()) {
+                *error = redact_url_fragments(error).into_owned();
+            }
+        }
         {
             let mut state = self.chain.lock();
             chained.sequence = state.sequence;
@@ -352,50 +374,229 @@ impl AuditLogger {
         })
     }
 
+    fn needs_rotation(&self) -> bool {
+        std::fs::metadata(&self.log_path).is_ok_and(|metadata| {
+            metadata.len() > 0
+                && metadata.len() / (1024 * 1024) >= u64::from(self.config.max_size_mb)
+        })
+    }
+
     /// Rotate log if it exceeds max size
     fn rotate_if_needed(&self) -> Result<()> {
-        if let Ok(metadata) = std::fs::metadata(&self.log_path) {
-            let current_size_mb = metadata.len() / (1024 * 1024);
-            if current_size_mb >= u64::from(self.config.max_size_mb) {
-                self.rotate()?;
-            }
+        if self.needs_rotation() {
+            self.rotate()?;
         }
         Ok(())
     }
 
-    /// Rotate the log file
+    /// Rotate the log file.
+    ///
+    /// Several sessions may share one audit log, so rotation runs under an
+    /// advisory file lock and re-checks the size once the lock is held. The
+    /// lock is only tried, never waited for: a writer that finds it taken
+    /// leaves rotation to the holder and appends to the active log, so
+    /// logging never blocks on another session. Appends themselves need no
+    /// lock — a writer that raced the rename lands its line in `.1.log`,
+    /// which readers still see.
+    ///
+    /// That is also why `.1.log` is never compressed: a late append could
+    /// land between copying it and removing it. With `compress_rotated` a
+    /// file is gzipped at the next rotation instead, after it has been
+    /// renamed to `.2.log`.
     fn rotate(&self) -> Result<()> {
-        for i in (1..10).rev() {
-            let old_name = format!("{}.{}.log", self.log_path.display(), i);
-            let new_name = format!("{}.{}.log", self.log_path.display(), i + 1);
-            let _ = std::fs::rename(&old_name, &new_name);
+        let Some(_lock) = RotationLock::try_acquire(&lock_path_for(&self.log_path))? else {
+            return Ok(());
+        };
+        if !self.needs_rotation() {
+            return Ok(());
         }
 
-        let rotated = format!("{}.1.log", self.log_path.display());
-        std::fs::rename(&self.log_path, &rotated)?;
+        let keep = self.config.max_rotated_files.max(1);
+        for (index, path) in rotated_files(&self.log_path).into_iter().rev() {
+            if index >= keep {
+                std::fs::remove_file(&path)?;
+                continue;
+            }
+            let shifted = rotated_path(&self.log_path, index + 1, is_gzip(&path));
+            std::fs::rename(&path, &shifted)?;
+            if self.config.compress_rotated && !is_gzip(&shifted) {
+                gzip_in_place(&shifted)?;
+            }
+        }
+
+        std::fs::rename(&self.log_path, rotated_path(&self.log_path, 1, false))?;
+
+        self.prune_rotated()
+    }
+
+    /// Delete rotated files beyond `max_rotated_files` or older than `max_age_days`.
+    ///
+    /// Callers hold the [`RotationLock`].
+    fn prune_rotated(&self) -> Result<()> {
+        let keep = self.config.max_rotated_files.max(1);
+        let max_age = (self.config.max_age_days > 0)
+            .then(|| Duration::from_secs(u64::from(self.config.max_age_days) * 86_400));
+        let now = SystemTime::now();
+
+        for (index, path) in rotated_files(&self.log_path) {
+            let expired = max_age.is_some_and(|max_age| {
+                std::fs::metadata(&path)
+                    .and_then(|m| m.modified())
+                    .is_ok_and(|modified| {
+                        now.duration_since(modified).unwrap_or_default() > max_age
+                    })
+            });
+            if index > keep || expired {
+                match std::fs::remove_file(&path) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
         Ok(())
     }
 }
 
-/// Recover chain state from an existing log file.
+/// Advisory lock serializing rotation across processes sharing one log.
 ///
-/// Returns the genesis state if the file does not exist or is empty.
-fn recover_chain_state(log_path: &Path) -> ChainState {
-    let file = match std::fs::File::open(log_path) {
-        Ok(f) => f,
-        Err(_) => {
-            return ChainState {
-                prev_hash: GENESIS_PREV_HASH.to_string(),
-                sequence: 0,
-            };
+/// An OS file lock on `<log>.lock`, released when the file is closed, so a
+/// crashed holder cannot leave it stuck and there is no stale lock to clean
+/// up. The lock file itself stays in place.
+struct RotationLock {
+    _file: File,
+}
+
+impl RotationLock {
+    /// Take the lock without waiting; `None` while another writer holds it.
+    fn try_acquire(path: &Path) -> Result<Option<Self>> {
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+        match file.try_lock() {
+            Ok(()) => Ok(Some(Self { _file: file })),
+            Err(TryLockError::WouldBlock) => Ok(None),
+            Err(TryLockError::Error(e)) => Err(e.into()),
         }
+    }
+}
+
+fn lock_path_for(log_path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.lock", log_path.display()))
+}
+
+fn rotated_path(log_path: &Path, index: u32, compressed: bool) -> PathBuf {
+    let suffix = if compressed { ".gz" } else { "" };
+    PathBuf::from(format!("{}.{index}.log{suffix}", log_path.display()))
+}
+
+fn is_gzip(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "gz")
+}
+
+/// Existing rotated files for `log_path`, ordered newest (index 1) first.
+fn rotated_files(log_path: &Path) -> Vec<(u32, PathBuf)> {
+    let (Some(dir), Some(name)) = (log_path.parent(), log_path.file_name()) else {
+        return Vec::new();
     };
+    let prefix = format!("{}.", name.to_string_lossy());
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut files = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let rest = file_name.strip_prefix(&prefix)?;
+            let index = rest
+                .strip_suffix(".log.gz")
+                .or_else(|| rest.strip_suffix(".log"))?
+                .parse::<u32>()
+                .ok()?;
+            Some((index, entry.path()))
+        })
+        .collect::<Vec<_>>();
+    files.sort_by_key(|(index, _)| *index);
+    files
+}
+
+fn gzip_in_place(path: &Path) -> Result<()> {
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+
+    let gz_path = PathBuf::from(format!("{}.gz", path.display()));
+    let mut input = std::fs::File::open(path)?;
+    let mut encoder = GzEncoder::new(std::fs::File::create(&gz_path)?, Compression::default());
+    std::io::copy(&mut input, &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+fn open_log_reader(path: &Path) -> std::io::Result<Box<dyn BufRead>> {
+    let file = std::fs::File::open(path)?;
+    if is_gzip(path) {
+        Ok(Box::new(BufReader::new(flate2::read::GzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+/// Read every retained audit event, oldest first, across rotated files
+/// (plain or gzipped) followed by the active log. Lines that fail to parse
+/// are skipped.
+pub fn read_events(log_path: &Path) -> Result<Vec<AuditEvent>> {
+    let mut sources = rotated_files(log_path)
+        .into_iter()
+        .rev()
+        .map(|(_, path)| path)
+        .collect::<Vec<_>>();
+    if log_path.exists() {
+        sources.push(log_path.to_path_buf());
+    }
+
+    let mut events = Vec::new();
+    for path in sources {
+        let reader = match open_log_reader(&path) {
+            Ok(reader) => reader,
+            // Pruned or rotated by another writer since the directory scan.
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e.into()),
+        };
+        events.extend(
+            reader
+                .lines()
+                .map_while(Result::ok)
+                .filter_map(|line| serde_json::from_str::<AuditEvent>(&line).ok()),
+        );
+    }
+    Ok(events)
+}
+
+/// Recover chain state from an existing log file.
+///
+/// Falls back to the most recent rotated file when the active log was just
+/// rotated away, so the chain continues across rotations and restarts.
+/// Returns the genesis state if no entries exist.
+fn recover_chain_state(log_path: &Path) -> ChainState {
+    let candidates = std::iter::once(log_path.to_path_buf())
+        .chain(rotated_files(log_path).into_iter().map(|(_, path)| path));
 
-    let reader = BufReader::new(file);
     let mut last_entry: Option<AuditEvent> = None;
-    for l in reader.lines().map_while(Result::ok) {
-        if let Ok(entry) = serde_json::from_str::<AuditEvent>(&l) {
-            last_entry = Some(entry);
+    for path in candidates {
+        let Ok(reader) = open_log_reader(&path) else {
+            continue;
+        };
+        for l in reader.lines().map_while(Result::ok) {
+            if let Ok(entry) = serde_json::from_str::<AuditEvent>(&l) {
+                last_entry = Some(entry);
+            }
+        }
+        if last_entry.is_some() {
+            break;
         }
     }
 
@@ -413,20 +614,29 @@ fn recover_chain_state(log_path: &Path) -> ChainState {
 
 /// Verify the integrity of an audit log's Merkle hash chain.
 ///
-/// Reads every entry from the log file and checks:
+/// Reads every retained entry, oldest first, across rotated files and the
+/// active log (see [`read_events`]) and checks:
 /// - Each `entry_hash` matches the recomputed `SHA-256(prev_hash || content)`.
 /// - `prev_hash` links to the preceding entry (or the genesis seed for the first).
 /// - Sequence numbers are contiguous starting from 0.
 /// - If a record has a `signature` field and `ZEROCLAW_AUDIT_SIGNING_KEY` is available,
 ///   verifies the HMAC-SHA256 signature over `entry_hash`.
 ///
+/// When rotated files exist and the oldest ones have been pruned, the chain
+/// is anchored on the first retained entry instead of sequence 0.
+///
 /// Returns `Ok(entry_count)` on success, or an error describing the first violation.
 pub fn verify_chain(log_path: &Path) -> Result<u64> {
-    let file = std::fs::File::open(log_path)?;
-    let reader = BufReader::new(file);
+    let rotated = !rotated_files(log_path).is_empty();
+    if !rotated && !log_path.exists() {
+        bail!("audit log not found: {}", log_path.display());
+    }
+    let events = read_events(log_path)?;
 
-    let mut expected_prev_hash = GENESIS_PREV_HASH.to_string();
-    let mut expected_sequence: u64 = 0;
+    let (mut expected_sequence, mut expected_prev_hash) = match events.first() {
+        Some(first) if rotated && first.sequence > 0 => (first.sequence, first.prev_hash.clone()),
+        _ => (0, GENESIS_PREV_HASH.to_string()),
+    };
 
     // Attempt to load signing key from environment (optional)
     let signing_key = std::env::var("ZEROCLAW_AUDIT_SIGNING_KEY")
@@ -434,18 +644,12 @@ pub fn verify_chain(log_path: &Path) -> Result<u64> {
         .and_then(|key_hex| hex::decode(&key_hex).ok())
         .filter(|key_bytes| key_bytes.len() == 32);
 
-    for (line_idx, line) in reader.lines().enumerate() {
-        let line = line?;
-        if line.trim().is_empty() {
-            continue;
-        }
-        let entry: AuditEvent = serde_json::from_str(&line)?;
-
+    for (entry_idx, entry) in events.iter().enumerate() {
         // Check sequence continuity
         if entry.sequence != expected_sequence {
             bail!(
-                "sequence gap at line {}: expected {}, got {}",
-                line_idx + 1,
+                "sequence gap at entry {}: expected {}, got {}",
+                entry_idx + 1,
                 expected_sequence,
                 entry.sequence
             );
@@ -454,8 +658,8 @@ pub fn verify_chain(log_path: &Path) -> Result<u64> {
         // Check prev_hash linkage
         if entry.prev_hash != expected_prev_hash {
             bail!(
-                "prev_hash mismatch at line {} (sequence {}): expected {}, got {}",
-                line_idx + 1,
+                "prev_hash mismatch at entry {} (sequence {}): expected {}, got {}",
+                entry_idx + 1,
                 entry.sequence,
                 expected_prev_hash,
                 entry.prev_hash
@@ -463,11 +667,11 @@ pub fn verify_chain(log_path: &Path) -> Result<u64> {
         }
 
         // Recompute and verify entry_hash
-        let recomputed = compute_entry_hash(&entry.prev_hash, &entry);
+        let recomputed = compute_entry_hash(&entry.prev_hash, entry);
         if entry.entry_hash != recomputed {
             bail!(
-                "entry_hash mismatch at line {} (sequence {}): expected {}, got {}",
-                line_idx + 1,
+                "entry_hash mismatch at entry {} (sequence {}): expected {}, got {}",
+                entry_idx + 1,
                 entry.sequence,
                 recomputed,
                 entry.entry_hash
@@ -487,8 +691,8 @@ pub fn verify_chain(log_path: &Path) -> Result<u64> {
 
                 if signature != &expected_sig {
                     bail!(
-                        "signature verification failed at line {} (sequence {}): signature mismatch",
-                        line_idx + 1,
+                        "signature verification failed at entry {} (sequence {}): signature mismatch",
+                        entry_idx + 1,
                         entry.sequence
                     );
                 }
@@ -500,7 +704,7 @@ pub fn verify_chain(log_path: &Path) -> Result<u64> {
         expected_sequence += 1;
     }
 
-    Ok(expected_sequence)
+    Ok(events.len() as u64)
 }
 
 #[cfg(test)]
@@ -671,6 +875,322 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn audit_rotation_prunes_beyond_max_rotated_files() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let config = AuditConfig {
+            enabled: true,
+            max_size_mb: 0, // Rotate before every write
+            max_rotated_files: 3,
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(config, tmp.path().to_path_buf())?;
+        for _ in 0..5 {
+            logger.log(&AuditEvent::new(AuditEventType::CommandExecution))?;
+        }
+
+        let log_path = tmp.path().join("audit.log");
+        let rotated: Vec<u32> = rotated_files(&log_path)
+            .into_iter()
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(rotated, vec![1, 2, 3]);
+
+        // The oldest event was pruned; the rest come back oldest-first.
+        let sequences: Vec<u64> = read_events(&log_path)?.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![1, 2, 3, 4]);
+        assert!(RotationLock::try_acquire(&lock_path_for(&log_path))?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn audit_rotates_once_the_size_limit_is_crossed() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let config = AuditConfig {
+            enabled: true,
+            max_size_mb: 1,
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(config, tmp.path().to_path_buf())?;
+        let log_path = tmp.path().join("audit.log");
+        let rotated = rotated_path(&log_path, 1, false);
+
+        let mut written = 0_u64;
+        while std::fs::metadata(&log_path).map_or(0, |m| m.len()) < 1024 * 1024 {
+            logger.log(&AuditEvent::new(AuditEventType::CommandExecution))?;
+            written += 1;
+        }
+        assert!(!rotated.exists(), "rotated before reaching the limit");
+
+        // The first write past the limit rotates the full file away.
+        logger.log(&AuditEvent::new(AuditEventType::CommandExecution))?;
+        assert!(std::fs::metadata(&rotated)?.len() >= 1024 * 1024);
+        assert!(std::fs::metadata(&log_path)?.len() < 1024);
+        assert!(!rotated_path(&log_path, 2, false).exists());
+
+        let sequences: Vec<u64> = read_events(&log_path)?.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, (0..=written).collect::<Vec<_>>());
+        assert_eq!(verify_chain(&log_path)?, written + 1);
+        Ok(())
+    }
+
+    #[test]
+    fn audit_logging_does_not_wait_for_a_held_rotation_lock() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let config = AuditConfig {
+            enabled: true,
+            max_size_mb: 0,
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(config, tmp.path().to_path_buf())?;
+        let log_path = tmp.path().join("audit.log");
+        logger.log(&AuditEvent::new(AuditEventType::SecurityEvent))?;
+
+        // Another session is mid-rotation: this one appends instead.
+        let held = RotationLock::try_acquire(&lock_path_for(&log_path))?.unwrap();
+        let started = std::time::Instant::now();
+        logger.log(&AuditEvent::new(AuditEventType::SecurityEvent))?;
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(!rotated_path(&log_path, 1, false).exists());
+
+        drop(held);
+        logger.log(&AuditEvent::new(AuditEventType::SecurityEvent))?;
+        assert!(rotated_path(&log_path, 1, false).exists());
+        let sequences: Vec<u64> = read_events(&log_path)?.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![0, 1, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn audit_rotation_compresses_and_stays_readable() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let config = AuditConfig {
+            enabled: true,
+            max_size_mb: 0,
+            compress_rotated: true,
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(config, tmp.path().to_path_buf())?;
+        for _ in 0..3 {
+            logger.log(&AuditEvent::new(AuditEventType::SecurityEvent))?;
+        }
+
+        // `.1.log` stays plain; it is compressed once it shifts to `.2.log`.
+        let log_path = tmp.path().join("audit.log");
+        assert!(rotated_path(&log_path, 1, false).exists());
+        assert!(rotated_path(&log_path, 2, true).exists());
+        assert!(!rotated_path(&log_path, 1, true).exists());
+        assert!(!rotated_path(&log_path, 2, false).exists());
+
+        let sequences: Vec<u64> = read_events(&log_path)?.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![0, 1, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn audit_compression_keeps_lines_appended_after_rotation() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let config = AuditConfig {
+            enabled: true,
+            max_size_mb: 0,
+            compress_rotated: true,
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(config, tmp.path().to_path_buf())?;
+        logger.log(&AuditEvent::new(AuditEventType::SecurityEvent))?;
+        logger.log(&AuditEvent::new(AuditEventType::SecurityEvent))?;
+
+        // A writer that opened the active log just before it was rotated
+        // appends to what is now `.1.log`.
+        let log_path = tmp.path().join("audit.log");
+        let mut late = AuditEvent::new(AuditEventType::SecurityEvent);
+        late.sequence = 99;
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(rotated_path(&log_path, 1, false))?;
+        writeln!(file, "{}", serde_json::to_string(&late)?)?;
+        drop(file);
+
+        logger.log(&AuditEvent::new(AuditEventType::SecurityEvent))?;
+        assert!(rotated_path(&log_path, 2, true).exists());
+        let sequences: Vec<u64> = read_events(&log_path)?.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![0, 99, 1, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn verify_chain_spans_rotated_and_compressed_files() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let log_path = tmp.path().join("audit.log");
+        let config = AuditConfig {
+            enabled: true,
+            max_size_mb: 0,
+            compress_rotated: true,
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(config.clone(), tmp.path().to_path_buf())?;
+        for _ in 0..5 {
+            logger.log(&AuditEvent::new(AuditEventType::CommandExecution))?;
+        }
+        assert!(rotated_path(&log_path, 4, true).exists());
+        assert_eq!(verify_chain(&log_path)?, 5);
+
+        // Once the oldest files are pruned, the chain is anchored on the
+        // first retained entry.
+        let pruning = AuditLogger::new(
+            AuditConfig {
+                max_rotated_files: 2,
+                ..config
+            },
+            tmp.path().to_path_buf(),
+        )?;
+        pruning.log(&AuditEvent::new(AuditEventType::CommandExecution))?;
+        let sequences: Vec<u64> = read_events(&log_path)?.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![3, 4, 5]);
+        assert_eq!(verify_chain(&log_path)?, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn audit_startup_prunes_files_older_than_max_age() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let log_path = tmp.path().join("audit.log");
+        let fresh = rotated_path(&log_path, 1, false);
+        let stale = rotated_path(&log_path, 2, false);
+        std::fs::write(&fresh, "")?;
+        std::fs::write(&stale, "")?;
+        std::fs::File::options()
+            .write(true)
+            .open(&stale)?
+            .set_modified(SystemTime::now() - Duration::from_secs(3 * 86_400))?;
+
+        let config = AuditConfig {
+            enabled: true,
+            max_age_days: 1,
+            ..Default::default()
+        };
+        let _logger = AuditLogger::new(config, tmp.path().to_path_buf())?;
+
+        assert!(fresh.exists());
+        assert!(
+            !stale.exists(),
+            "rotated file past max_age_days must be pruned"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn audit_recovers_chain_from_rotated_file() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let config = AuditConfig {
+            enabled: true,
+            max_size_mb: 10,
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(config.clone(), tmp.path().to_path_buf())?;
+        logger.log(&AuditEvent::new(AuditEventType::CommandExecution))?;
+        logger.log(&AuditEvent::new(AuditEventType::CommandExecution))?;
+        drop(logger);
+
+        // Restart right after a rotation moved the active log away.
+        let log_path = tmp.path().join("audit.log");
+        std::fs::rename(&log_path, rotated_path(&log_path, 1, false))?;
+
+        let logger = AuditLogger::new(config, tmp.path().to_path_buf())?;
+        logger.log(&AuditEvent::new(AuditEventType::CommandExecution))?;
+        let sequences: Vec<u64> = read_events(&log_path)?.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![0, 1, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn audit_concurrent_loggers_lose_no_events() -> Result<()> {
+        const THREADS: usize = 4;
+        const EVENTS_PER_THREAD: usize = 10;
+
+        let tmp = TempDir::new()?;
+        let config = AuditConfig {
+            enabled: true,
+            max_size_mb: 0,
+            max_rotated_files: 1_000,
+            ..Default::default()
+        };
+
+        std::thread::scope(|scope| {
+            for _ in 0..THREADS {
+                let config = config.clone();
+                let dir = tmp.path().to_path_buf();
+                scope.spawn(move || {
+                    let logger = AuditLogger::new(config, dir).unwrap();
+                    for _ in 0..EVENTS_PER_THREAD {
+                        logger
+                            .log(&AuditEvent::new(AuditEventType::CommandExecution))
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        let log_path = tmp.path().join("audit.log");
+        assert_eq!(read_events(&log_path)?.len(), THREADS * EVENTS_PER_THREAD);
+        assert!(RotationLock::try_acquire(&lock_path_for(&log_path))?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn audit_redacts_url_fragments_by_default() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let config = AuditConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(config, tmp.path().to_path_buf())?;
+        let event = AuditEvent::new(AuditEventType::CommandExecution).with_action(
+            "browser_open https://app.example.com/callback#access_token=SECRET123&token_type=bearer"
+                .into(),
+            "low".into(),
+            true,
+            true,
+        )
+        .with_result(
+            false,
+            None,
+            12,
+            Some("HTTP request failed for https://app.example.com/cb#id_token=SECRET456".into()),
+        );
+        logger.log(&event)?;
+
+        let content = std::fs::read_to_string(tmp.path().join("audit.log"))?;
+        assert!(!content.contains("SECRET456"), "{content}");
+        assert!(content.contains("https://app.example.com/cb#[redacted]"));
+        assert!(!content.contains("SECRET123"), "{content}");
+        assert!(!content.contains("access_token"), "{content}");
+        assert!(content.contains("https://app.example.com/callback#[redacted]"));
+        Ok(())
+    }
+
+    #[test]
+    fn audit_log_fragments_override_keeps_fragments() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let config = AuditConfig {
+            enabled: true,
+            log_fragments: true,
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(config, tmp.path().to_path_buf())?;
+        let event = AuditEvent::new(AuditEventType::CommandExecution).with_action(
+            "web_fetch https://docs.example.com/guide#install".into(),
+            "low".into(),
+            true,
+            true,
+        );
+        logger.log(&event)?;
+
+        let content = std::fs::read_to_string(tmp.path().join("audit.log"))?;
+        assert!(content.contains("https://docs.example.com/guide#install"));
+        Ok(())
+    }
+
     // ── Merkle hash-chain tests ─────────────────────────────
 
     #[test]
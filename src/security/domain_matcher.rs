@@ -1,3 +1,4 @@
+use crate::tools::url_validation::HostKey;
 use anyhow::{Result, bail};
 use std::collections::BTreeSet;
 
@@ -62,13 +63,13 @@ impl DomainMatcher {
     }
 
     pub fn is_gated(&self, domain: &str) -> bool {
-        let Some(normalized_domain) = normalize_domain(domain) else {
+        let Some(host) = host_key(domain) else {
             return false;
         };
 
         self.patterns
             .iter()
-            .any(|pattern| domain_matches_pattern(pattern, &normalized_domain))
+            .any(|pattern| domain_matches_pattern(pattern, &host))
     }
 
     pub fn expand_categories(categories: &[String]) -> Result<Vec<String>> {
@@ -97,34 +98,20 @@ impl DomainMatcher {
     }
 }
 
-fn normalize_domain(raw: &str) -> Option<String> {
-    let mut domain = raw.trim().to_ascii_lowercase();
-    if domain.is_empty() {
-        return None;
-    }
-
-    if let Some((_, rest)) = domain.split_once("://") {
-        domain = rest.to_string();
-    }
-
-    domain = domain
-        .split(['/', '?', '#'])
-        .next()
-        .unwrap_or_default()
-        .to_string();
-    if let Some((_, host)) = domain.rsplit_once('@') {
-        domain = host.to_string();
-    }
-    if let Some((host, _port)) = domain.split_once(':') {
-        domain = host.to_string();
-    }
-    domain = domain.trim_end_matches('.').to_string();
-
-    if domain.is_empty() {
-        None
-    } else {
-        Some(domain)
-    }
+/// The [`HostKey`] of a bare host or of the host in a URL.
+fn host_key(raw: &str) -> Option<HostKey> {
+    let raw = raw.trim();
+    let rest = raw.split_once("://").map_or(raw, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    let key = HostKey::from(host);
+    (!key.as_str().is_empty()).then_some(key)
 }
 
 fn normalize_pattern(raw: &str) -> Result<String> {
@@ -161,14 +148,14 @@ fn normalize_pattern(raw: &str) -> Result<String> {
     Ok(pattern)
 }
 
-fn domain_matches_pattern(pattern: &str, domain: &str) -> bool {
+fn domain_matches_pattern(pattern: &str, host: &HostKey) -> bool {
     if pattern == "*" {
         return true;
     }
     if !pattern.contains('*') {
-        return pattern == domain;
+        return HostKey::from(pattern) == *host;
     }
-    wildcard_match(pattern.as_bytes(), domain.as_bytes())
+    wildcard_match(pattern.as_bytes(), host.as_str().as_bytes())
 }
 
 fn wildcard_match(pattern: &[u8], value: &[u8]) -> bool {
@@ -236,6 +223,35 @@ mod tests {
         assert!(!matcher.is_gated("developer.mozilla.org"));
     }
 
+    #[test]
+    fn equivalent_host_spellings_are_gated_alike() {
+        let matcher = DomainMatcher::new(
+            &["accounts.google.com".to_string(), "*.chase.com".to_string()],
+            &[] as &[String],
+        )
+        .unwrap();
+        for domain in [
+            "Accounts.Google.COM",
+            "accounts.google.com.",
+            "https://ACCOUNTS.google.com./login",
+            "https://user@accounts.google.com:443/",
+            "WWW.Chase.com.",
+            "https://secure.chase.com.:8443/",
+        ] {
+            assert!(matcher.is_gated(domain), "{domain}");
+        }
+        assert!(!matcher.is_gated("accounts.google.com.evil.test"));
+    }
+
+    #[test]
+    fn numeric_host_spellings_match_ip_patterns() {
+        let matcher = DomainMatcher::new(&["10.0.0.1".to_string()], &[] as &[String]).unwrap();
+        assert!(matcher.is_gated("http://10.0.0.1./admin"));
+        assert!(matcher.is_gated("0x0a000001"));
+        assert!(matcher.is_gated("http://167772161:8080/"));
+        assert!(!matcher.is_gated("10.0.0.2"));
+    }
+
     #[test]
     fn non_matching_domain_returns_false() {
         let matcher =
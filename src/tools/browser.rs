@@ -6,6 +6,7 @@
 //! Computer-use (OS-level) actions are supported via an optional sidecar endpoint.
 
 use super::traits::{Tool, ToolResult};
+use super::url_validation::{HostKey, NumericHost, classify_numeric_host};
 use crate::security::SecurityPolicy;
 use anyhow::Context;
 use async_trait::async_trait;
@@ -2094,7 +2095,7 @@ fn is_private_host(host: &str) -> bool {
         return true;
     }
 
-    // Parse as IP address to catch all representations (decimal, hex, octal, mapped)
+    // Parse as IP address to catch canonical and IPv4-mapped forms
     if let Ok(ip) = bare.parse::<std::net::IpAddr>() {
         return match ip {
             std::net::IpAddr::V4(v4) => is_non_global_v4(v4),
@@ -2102,7 +2103,13 @@ fn is_private_host(host: &str) -> bool {
         };
     }
 
-    false
+    // Legacy notations (dword, hex, octal, zero-padded) that the resolver
+    // would still map to an address
+    match classify_numeric_host(bare) {
+        NumericHost::Ipv4(v4) => is_non_global_v4(v4),
+        NumericHost::Invalid => true,
+        NumericHost::NotNumeric => false,
+    }
 }
 
 /// Returns `true` for any IPv4 address that is not globally routable.
@@ -2179,18 +2186,11 @@ fn ensure_browser_env(cmd: &mut Command) {
 }
 
 fn host_matches_allowlist(host: &str, allowed: &[String]) -> bool {
+    let host = HostKey::from(host);
     allowed.iter().any(|pattern| {
-        if pattern == "*" {
-            return true;
-        }
-        if pattern.starts_with("*.") {
-            // Wildcard subdomain match
-            let suffix = &pattern[1..]; // ".example.com"
-            host.ends_with(suffix) || host == &pattern[2..]
-        } else {
-            // Exact match or subdomain
-            host == pattern || host.ends_with(&format!(".{pattern}"))
-        }
+        // `*.example.com` matches the apex and any subdomain, same as `example.com`.
+        let domain = pattern.strip_prefix("*.").unwrap_or(pattern);
+        host.is_within(&HostKey::from(domain))
     })
 }
 
@@ -2246,6 +2246,17 @@ mod tests {
         assert!(!is_private_host("google.com"));
     }
 
+    #[test]
+    fn is_private_host_catches_alternate_ipv4_notations() {
+        assert!(is_private_host("2130706433"));
+        assert!(is_private_host("0x7f000001"));
+        assert!(is_private_host("0177.0.0.1"));
+        assert!(is_private_host("0x7f.0.0.1"));
+        assert!(is_private_host("0xa9fea9fe"));
+        assert!(is_private_host("08.0.0.1"));
+        assert!(!is_private_host("0x08080808"));
+    }
+
     #[test]
     fn is_private_host_blocks_multicast_and_reserved() {
         assert!(is_private_host("224.0.0.1")); // multicast
@@ -2312,6 +2323,14 @@ mod tests {
         assert!(!host_matches_allowlist("other.com", &allowed));
     }
 
+    #[test]
+    fn host_matches_allowlist_canonicalizes_hosts() {
+        let allowed = vec!["Example.com.".into()];
+        assert!(host_matches_allowlist("SUB.example.COM.", &allowed));
+        assert!(host_matches_allowlist("[::1]", &["::1".into()]));
+        assert!(!host_matches_allowlist("example.com.evil", &allowed));
+    }
+
     #[test]
     fn host_matches_allowlist_star() {
         let allowed = vec!["*".into()];
@@ -12,6 +12,7 @@
 
 use crate::security::SecurityPolicy;
 use crate::tools::traits::{Tool, ToolResult};
+use crate::tools::url_validation::{HostKey, host_key_matches, normalize_host_keys};
 use async_trait::async_trait;
 use regex::Regex;
 use schemars::JsonSchema;
@@ -69,12 +70,23 @@ impl Default for BrowserDelegateConfig {
 pub struct BrowserDelegateTool {
     security: Arc<SecurityPolicy>,
     config: BrowserDelegateConfig,
+    /// `config.allowed_domains` as host keys.
+    allowed_hosts: Vec<HostKey>,
+    /// `config.blocked_domains` as host keys.
+    blocked_hosts: Vec<HostKey>,
 }
 
 impl BrowserDelegateTool {
     /// Create a new `BrowserDelegateTool` with the given security policy and config.
     pub fn new(security: Arc<SecurityPolicy>, config: BrowserDelegateConfig) -> Self {
-        Self { security, config }
+        let allowed_hosts = normalize_host_keys(config.allowed_domains.clone());
+        let blocked_hosts = normalize_host_keys(config.blocked_domains.clone());
+        Self {
+            security,
+            config,
+            allowed_hosts,
+            blocked_hosts,
+        }
     }
 
     /// Build the CLI command for a browser task.
@@ -146,38 +158,24 @@ impl BrowserDelegateTool {
         }
 
         // Check blocked domains first (deny takes precedence)
-        for blocked in &self.config.blocked_domains {
-            if domain_matches(&domain, blocked) {
-                anyhow::bail!("domain '{}' is blocked by browser_delegate policy", domain);
-            }
+        let host = HostKey::from(domain.as_str());
+        if host_key_matches(&host, &self.blocked_hosts) {
+            anyhow::bail!("domain '{}' is blocked by browser_delegate policy", domain);
         }
 
         // If allowed_domains is non-empty, it acts as an allowlist
-        if !self.config.allowed_domains.is_empty() {
-            let allowed = self
-                .config
-                .allowed_domains
-                .iter()
-                .any(|d| domain_matches(&domain, d));
-            if !allowed {
-                anyhow::bail!(
-                    "domain '{}' is not in browser_delegate allowed_domains",
-                    domain
-                );
-            }
+        if !self.config.allowed_domains.is_empty() && !host_key_matches(&host, &self.allowed_hosts)
+        {
+            anyhow::bail!(
+                "domain '{}' is not in browser_delegate allowed_domains",
+                domain
+            );
         }
 
         Ok(())
     }
 }
 
-/// Check whether `domain` matches a pattern (exact or suffix match).
-fn domain_matches(domain: &str, pattern: &str) -> bool {
-    let d = domain.to_lowercase();
-    let p = pattern.to_lowercase();
-    d == p || d.ends_with(&format!(".{}", p))
-}
-
 /// Maximum stderr bytes to capture from the subprocess.
 const MAX_STDERR_CHARS: usize = 512;
 
@@ -587,24 +585,43 @@ mod tests {
 
     // ── Domain matching ─────────────────────────────────────────────
 
+    fn allowlist_admits(url: &str, allowed: &str) -> bool {
+        test_tool(config_with_domains(vec![allowed.into()], vec![]))
+            .validate_url(url)
+            .is_ok()
+    }
+
     #[test]
     fn domain_matches_exact() {
-        assert!(domain_matches("example.com", "example.com"));
+        assert!(allowlist_admits("https://example.com/", "example.com"));
     }
 
     #[test]
     fn domain_matches_subdomain() {
-        assert!(domain_matches("sub.example.com", "example.com"));
+        assert!(allowlist_admits("https://sub.example.com/", "example.com"));
     }
 
     #[test]
     fn domain_matches_case_insensitive() {
-        assert!(domain_matches("Example.COM", "example.com"));
+        assert!(allowlist_admits("https://Example.COM/", "example.com"));
     }
 
     #[test]
     fn domain_does_not_match_partial() {
-        assert!(!domain_matches("notexample.com", "example.com"));
+        assert!(!allowlist_admits("https://notexample.com/", "example.com"));
+    }
+
+    #[test]
+    fn blocklist_matches_trailing_dot_host() {
+        let tool = test_tool(config_with_domains(vec![], vec!["blocked.com".into()]));
+        for url in [
+            "https://blocked.com./",
+            "https://evil.blocked.com./page",
+            "https://EVIL.Blocked.COM./",
+        ] {
+            let err = tool.validate_url(url).unwrap_err().to_string();
+            assert!(err.contains("blocked"), "{url}: {err}");
+        }
     }
 
     // ── Execute edge cases ──────────────────────────────────────────
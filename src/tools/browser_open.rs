@@ -1,4 +1,5 @@
 use super::traits::{Tool, ToolResult};
+use super::url_validation::{SchemeConstraint, UrlPolicy, UrlValidationError, ValidatedUrl};
 use crate::security::SecurityPolicy;
 use async_trait::async_trait;
 use serde_json::json;
@@ -7,49 +8,38 @@ use std::sync::Arc;
 /// Open approved HTTPS URLs in the system default browser (no scraping, no DOM automation).
 pub struct BrowserOpenTool {
     security: Arc<SecurityPolicy>,
-    allowed_domains: Vec<String>,
+    policy: UrlPolicy,
 }
 
 impl BrowserOpenTool {
     pub fn new(security: Arc<SecurityPolicy>, allowed_domains: Vec<String>) -> Self {
         Self {
             security,
-            allowed_domains: normalize_allowed_domains(allowed_domains),
+            policy: UrlPolicy::new(SchemeConstraint::HttpsOnly, allowed_domains),
         }
     }
 
-    fn validate_url(&self, raw_url: &str) -> anyhow::Result<String> {
-        let url = raw_url.trim();
-
-        if url.is_empty() {
-            anyhow::bail!("URL cannot be empty");
-        }
-
-        if url.chars().any(char::is_whitespace) {
-            anyhow::bail!("URL cannot contain whitespace");
-        }
-
-        if !url.starts_with("https://") {
-            anyhow::bail!("Only https:// URLs are allowed");
-        }
-
-        if self.allowed_domains.is_empty() {
-            anyhow::bail!(
-                "Browser tool is enabled but no allowed_domains are configured. Add [browser].allowed_domains in config.toml"
-            );
-        }
-
-        let host = extract_host(url)?;
+    fn validate_url(&self, raw_url: &str) -> Result<ValidatedUrl, UrlValidationError> {
+        self.policy.validate(raw_url)
+    }
+}
 
-        if is_private_or_local_host(&host) {
-            anyhow::bail!("Blocked local/private host: {host}");
+/// Map a validation failure to the operator-facing message for this tool.
+fn describe_url_error(err: &UrlValidationError) -> String {
+    match err {
+        UrlValidationError::NoAllowlistConfigured => {
+            "Browser tool is enabled but no allowed_domains are configured. \
+             Add [browser].allowed_domains in config.toml"
+                .into()
         }
-
-        if !host_matches_allowlist(&host, &self.allowed_domains) {
-            anyhow::bail!("Host '{host}' is not in browser.allowed_domains");
+        UrlValidationError::NotInAllowlist { host } => format!(
+            "Host '{host}' is not in browser.allowed_domains. \
+             Add this domain to [browser].allowed_domains in config.toml to allow it"
+        ),
+        UrlValidationError::Ipv6NotSupported => {
+            "IPv6 hosts are not supported in browser_open".into()
         }
-
-        Ok(url.to_string())
+        other => other.to_string(),
     }
 }
 
@@ -104,12 +94,12 @@ impl Tool for BrowserOpenTool {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(e.to_string()),
+                    error: Some(describe_url_error(&e)),
                 });
             }
         };
 
-        match open_in_system_browser(&url).await {
+        match open_in_system_browser(url.as_str()).await {
             Ok(()) => Ok(ToolResult {
                 success: true,
                 output: format!("Opened in system browser: {url}"),
@@ -231,131 +221,6 @@ async fn open_in_system_browser(url: &str) -> anyhow::Result<()> {
     }
 }
 
-fn normalize_allowed_domains(domains: Vec<String>) -> Vec<String> {
-    let mut normalized = domains
-        .into_iter()
-        .filter_map(|d| normalize_domain(&d))
-        .collect::<Vec<_>>();
-    normalized.sort_unstable();
-    normalized.dedup();
-    normalized
-}
-
-fn normalize_domain(raw: &str) -> Option<String> {
-    let mut d = raw.trim().to_lowercase();
-    if d.is_empty() {
-        return None;
-    }
-
-    if let Some(stripped) = d.strip_prefix("https://") {
-        d = stripped.to_string();
-    } else if let Some(stripped) = d.strip_prefix("http://") {
-        d = stripped.to_string();
-    }
-
-    if let Some((host, _)) = d.split_once('/') {
-        d = host.to_string();
-    }
-
-    d = d.trim_start_matches('.').trim_end_matches('.').to_string();
-
-    if let Some((host, _)) = d.split_once(':') {
-        d = host.to_string();
-    }
-
-    if d.is_empty() || d.chars().any(char::is_whitespace) {
-        return None;
-    }
-
-    Some(d)
-}
-
-fn extract_host(url: &str) -> anyhow::Result<String> {
-    let rest = url
-        .strip_prefix("https://")
-        .ok_or_else(|| anyhow::anyhow!("Only https:// URLs are allowed"))?;
-
-    let authority = rest
-        .split(['/', '?', '#'])
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("Invalid URL"))?;
-
-    if authority.is_empty() {
-        anyhow::bail!("URL must include a host");
-    }
-
-    if authority.contains('@') {
-        anyhow::bail!("URL userinfo is not allowed");
-    }
-
-    if authority.starts_with('[') {
-        anyhow::bail!("IPv6 hosts are not supported in browser_open");
-    }
-
-    let host = authority
-        .split(':')
-        .next()
-        .unwrap_or_default()
-        .trim()
-        .trim_end_matches('.')
-        .to_lowercase();
-
-    if host.is_empty() {
-        anyhow::bail!("URL must include a valid host");
-    }
-
-    Ok(host)
-}
-
-fn host_matches_allowlist(host: &str, allowed_domains: &[String]) -> bool {
-    if allowed_domains.iter().any(|domain| domain == "*") {
-        return true;
-    }
-
-    allowed_domains.iter().any(|domain| {
-        host == domain
-            || host
-                .strip_suffix(domain)
-                .is_some_and(|prefix| prefix.ends_with('.'))
-    })
-}
-
-fn is_private_or_local_host(host: &str) -> bool {
-    let has_local_tld = host
-        .rsplit('.')
-        .next()
-        .is_some_and(|label| label == "local");
-
-    if host == "localhost" || host.ends_with(".localhost") || has_local_tld || host == "::1" {
-        return true;
-    }
-
-    if let Some([a, b, _, _]) = parse_ipv4(host) {
-        return a == 0
-            || a == 10
-            || a == 127
-            || (a == 169 && b == 254)
-            || (a == 172 && (16..=31).contains(&b))
-            || (a == 192 && b == 168)
-            || (a == 100 && (64..=127).contains(&b));
-    }
-
-    false
-}
-
-fn parse_ipv4(host: &str) -> Option<[u8; 4]> {
-    let parts: Vec<&str> = host.split('.').collect();
-    if parts.len() != 4 {
-        return None;
-    }
-
-    let mut octets = [0_u8; 4];
-    for (i, part) in parts.iter().enumerate() {
-        octets[i] = part.parse::<u8>().ok()?;
-    }
-    Some(octets)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -373,26 +238,20 @@ mod tests {
     }
 
     #[test]
-    fn normalize_domain_strips_scheme_path_and_case() {
-        let got = normalize_domain("  HTTPS://Docs.Example.com/path ").unwrap();
-        assert_eq!(got, "docs.example.com");
-    }
-
-    #[test]
-    fn normalize_allowed_domains_deduplicates() {
-        let got = normalize_allowed_domains(vec![
-            "example.com".into(),
-            "EXAMPLE.COM".into(),
-            "https://example.com/".into(),
-        ]);
-        assert_eq!(got, vec!["example.com".to_string()]);
+    fn validate_accepts_exact_domain() {
+        let tool = test_tool(vec!["example.com"]);
+        let got = tool.validate_url("https://example.com/docs").unwrap();
+        assert_eq!(got.as_str(), "https://example.com/docs");
+        assert_eq!(got.host(), "example.com");
     }
 
     #[test]
-    fn validate_accepts_exact_domain() {
+    fn validate_preserves_fragment_for_the_browser() {
         let tool = test_tool(vec!["example.com"]);
-        let got = tool.validate_url("https://example.com/docs").unwrap();
-        assert_eq!(got, "https://example.com/docs");
+        let got = tool
+            .validate_url("https://example.com/app#/settings?tab=keys")
+            .unwrap();
+        assert_eq!(got.as_str(), "https://example.com/app#/settings?tab=keys");
     }
 
     #[test]
@@ -410,51 +269,53 @@ mod tests {
     #[test]
     fn validate_wildcard_allowlist_still_rejects_private_host() {
         let tool = test_tool(vec!["*"]);
-        let err = tool
-            .validate_url("https://localhost:8443")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("local/private"));
+        let err = tool.validate_url("https://localhost:8443").unwrap_err();
+        assert!(matches!(err, UrlValidationError::PrivateOrLocalHost { .. }));
     }
 
     #[test]
     fn validate_rejects_http() {
         let tool = test_tool(vec!["example.com"]);
-        let err = tool
-            .validate_url("http://example.com")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("https://"));
+        let err = tool.validate_url("http://example.com").unwrap_err();
+        assert_eq!(
+            err,
+            UrlValidationError::DisallowedScheme {
+                required: SchemeConstraint::HttpsOnly
+            }
+        );
     }
 
     #[test]
     fn validate_rejects_localhost() {
         let tool = test_tool(vec!["localhost"]);
-        let err = tool
-            .validate_url("https://localhost:8080")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("local/private"));
+        let err = tool.validate_url("https://localhost:8080").unwrap_err();
+        assert!(matches!(err, UrlValidationError::PrivateOrLocalHost { .. }));
     }
 
     #[test]
     fn validate_rejects_private_ipv4() {
         let tool = test_tool(vec!["192.168.1.5"]);
-        let err = tool
-            .validate_url("https://192.168.1.5")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("local/private"));
+        let err = tool.validate_url("https://192.168.1.5").unwrap_err();
+        assert!(matches!(err, UrlValidationError::PrivateOrLocalHost { .. }));
+    }
+
+    #[test]
+    fn validate_rejects_shared_address_space() {
+        let tool = test_tool(vec!["*"]);
+        let err = tool.validate_url("https://100.64.0.1").unwrap_err();
+        assert!(matches!(err, UrlValidationError::PrivateOrLocalHost { .. }));
     }
 
     #[test]
     fn validate_rejects_allowlist_miss() {
         let tool = test_tool(vec!["example.com"]);
-        let err = tool
-            .validate_url("https://google.com")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("allowed_domains"));
+        let err = tool.validate_url("https://google.com").unwrap_err();
+        assert_eq!(
+            err,
+            UrlValidationError::NotInAllowlist {
+                host: "google.com".into()
+            }
+        );
     }
 
     #[test]
@@ -462,42 +323,35 @@ mod tests {
         let tool = test_tool(vec!["example.com"]);
         let err = tool
             .validate_url("https://example.com/hello world")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("whitespace"));
+            .unwrap_err();
+        assert_eq!(err, UrlValidationError::ContainsWhitespace);
     }
 
     #[test]
     fn validate_rejects_userinfo() {
         let tool = test_tool(vec!["example.com"]);
-        let err = tool
-            .validate_url("https://user@example.com")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("userinfo"));
+        let err = tool.validate_url("https://user@example.com").unwrap_err();
+        assert_eq!(err, UrlValidationError::UserinfoNotAllowed);
     }
 
     #[test]
     fn validate_requires_allowlist() {
         let security = Arc::new(SecurityPolicy::default());
         let tool = BrowserOpenTool::new(security, vec![]);
-        let err = tool
-            .validate_url("https://example.com")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("allowed_domains"));
+        let err = tool.validate_url("https://example.com").unwrap_err();
+        assert_eq!(err, UrlValidationError::NoAllowlistConfigured);
     }
 
     #[test]
-    fn parse_ipv4_valid() {
-        assert_eq!(parse_ipv4("1.2.3.4"), Some([1, 2, 3, 4]));
-    }
-
-    #[test]
-    fn parse_ipv4_invalid() {
-        assert_eq!(parse_ipv4("1.2.3"), None);
-        assert_eq!(parse_ipv4("1.2.3.999"), None);
-        assert_eq!(parse_ipv4("not-an-ip"), None);
+    fn allowlist_miss_message_points_at_config() {
+        let msg = describe_url_error(&UrlValidationError::NotInAllowlist {
+            host: "google.com".into(),
+        });
+        assert!(msg.contains("[browser].allowed_domains"));
+        let msg = describe_url_error(&UrlValidationError::PrivateOrLocalHost {
+            host: "localhost".into(),
+        });
+        assert!(!msg.contains("allowed_domains"));
     }
 
     #[tokio::test]
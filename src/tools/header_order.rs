@@ -0,0 +1,177 @@
+//! Deterministic outbound header order for `web_fetch` and `http_request`.
+//!
+//! Headers are written in a canonical order so that the pre-send
+//! interceptor, replay fixtures and anything hashing a request all see the
+//! same sequence on every run:
+//!
+//! 1. `host`
+//! 2. the names in [`CANONICAL_ORDER`], in that order
+//! 3. every other name, alphabetically
+//!
+//! Repeated values of one header keep their relative order. Headers the HTTP
+//! stack adds while writing the request (`content-length`,
+//! `transfer-encoding`) follow all of these.
+//!
+//! Names go on the wire lowercase. Hosts matching a tool's
+//! `browser_like_casing_domains` get `Title-Case` names instead. Preserving
+//! the casing a model or operator typed is not supported: reqwest hands
+//! hyper only a `HeaderMap`, whose names are always lowercase, and drops the
+//! request extensions hyper would read a per-request case map from.
+
+use super::url_validation::{HostKey, normalize_domain};
+use reqwest::header::{ACCEPT, HOST, HeaderMap, HeaderName, HeaderValue};
+
+/// Header names placed right after `host`, in this order.
+pub const CANONICAL_ORDER: &[&str] = &[
+    "user-agent",
+    "accept",
+    "accept-language",
+    "accept-encoding",
+    "content-type",
+    "authorization",
+    "cookie",
+];
+
+/// Normalize `browser_like_casing_domains` for matching with
+/// [`host_key_matches`](super::url_validation::host_key_matches).
+pub fn casing_domains(domains: &[String]) -> Vec<HostKey> {
+    domains
+        .iter()
+        .filter_map(|domain| normalize_domain(domain).map(HostKey::from))
+        .collect()
+}
+
+/// Fill in the headers the client would otherwise add after the fact and
+/// put everything in canonical order.
+///
+/// `host` and `accept: */*` are set explicitly, with the same values the
+/// client would use, so that they take their canonical position instead of
+/// being appended at send time.
+pub fn canonicalize_headers(request: &mut reqwest::Request) {
+    if !request.headers().contains_key(HOST) {
+        if let Some(host) = host_header_value(request.url()) {
+            request.headers_mut().insert(HOST, host);
+        }
+    }
+    request
+        .headers_mut()
+        .entry(ACCEPT)
+        .or_insert(HeaderValue::from_static("*/*"));
+    sort_headers(request.headers_mut());
+}
+
+/// Reorder `headers` canonically, keeping repeated values in order.
+pub fn sort_headers(headers: &mut HeaderMap) {
+    let mut names: Vec<HeaderName> = headers.keys().cloned().collect();
+    names.sort_by(|a, b| rank(a).cmp(&rank(b)));
+
+    let mut sorted = HeaderMap::with_capacity(headers.len());
+    for name in names {
+        for value in headers.get_all(&name) {
+            sorted.append(name.clone(), value.clone());
+        }
+    }
+    *headers = sorted;
+}
+
+fn rank(name: &HeaderName) -> (usize, &str) {
+    let name = name.as_str();
+    if name == HOST.as_str() {
+        return (0, "");
+    }
+    match CANONICAL_ORDER.iter().position(|known| *known == name) {
+        Some(index) => (index + 1, ""),
+        None => (CANONICAL_ORDER.len() + 1, name),
+    }
+}
+
+/// `Host` value for `url`: the host, plus the port when it is not the default.
+fn host_header_value(url: &reqwest::Url) -> Option<HeaderValue> {
+    let host = url.host_str()?;
+    let value = match url.port() {
+        Some(port) => format!("{host}:{port}"),
+        None => host.to_string(),
+    };
+    HeaderValue::from_str(&value).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::url_validation::host_key_matches;
+
+    fn names(headers: &HeaderMap) -> Vec<&str> {
+        headers.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    #[test]
+    fn orders_host_then_known_names_then_alphabetically() {
+        let client = reqwest::Client::new();
+        let mut request = client
+            .post("https://api.example.com:8443/v1")
+            .header("X-Trace", "t")
+            .header("Authorization", "Bearer k")
+            .header("content-type", "application/json")
+            .header("Accept-Language", "en")
+            .header("b-custom", "1")
+            .header("x-forwarded-for", "a")
+            .header("User-Agent", "ua")
+            .build()
+            .unwrap();
+        request
+            .headers_mut()
+            .append("x-forwarded-for", HeaderValue::from_static("b"));
+
+        canonicalize_headers(&mut request);
+
+        assert_eq!(
+            names(request.headers()),
+            vec![
+                "host",
+                "user-agent",
+                "accept",
+                "accept-language",
+                "content-type",
+                "authorization",
+                "b-custom",
+                "x-forwarded-for",
+                "x-forwarded-for",
+                "x-trace",
+            ]
+        );
+        assert_eq!(request.headers()["host"], "api.example.com:8443");
+        assert_eq!(request.headers()["accept"], "*/*");
+        let forwarded: Vec<_> = request
+            .headers()
+            .get_all("x-forwarded-for")
+            .iter()
+            .collect();
+        assert_eq!(forwarded, ["a", "b"]);
+    }
+
+    #[test]
+    fn keeps_supplied_host_and_accept() {
+        let client = reqwest::Client::new();
+        let mut request = client
+            .get("http://example.com/")
+            .header("accept", "application/json")
+            .header("host", "virtual.example")
+            .build()
+            .unwrap();
+        canonicalize_headers(&mut request);
+        assert_eq!(request.headers()["host"], "virtual.example");
+        assert_eq!(request.headers()["accept"], "application/json");
+        assert_eq!(names(request.headers()), vec!["host", "accept"]);
+    }
+
+    #[test]
+    fn casing_domains_match_hosts_and_subdomains() {
+        let domains = casing_domains(&["WAF.Example.com".into(), "not a domain".into()]);
+        assert_eq!(domains, vec![HostKey::from("waf.example.com")]);
+        assert!(host_key_matches(
+            &HostKey::from("api.waf.example.com"),
+            &domains
+        ));
+        assert!(!host_key_matches(&HostKey::from("example.com"), &domains));
+    }
+}
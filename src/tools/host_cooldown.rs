@@ -0,0 +1,312 @@
+//! Per-host cooldowns after HTTP 429, shared by the network tools.
+//!
+//! When a host answers 429, every tool holding the same [`HostCooldowns`]
+//! stops sending to it until the `Retry-After` period ends. A request made
+//! during a cooldown waits it out when the wait fits inside the request
+//! timeout and fails fast with the time remaining otherwise. Any successful
+//! response from the host ends its cooldown early.
+
+use super::url_validation::HostKey;
+use parking_lot::Mutex;
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Cooldown applied when a 429 carries no usable `Retry-After`.
+pub const DEFAULT_COOLDOWN_SECS: u64 = 30;
+/// Upper bound on a server-requested cooldown.
+pub const MAX_COOLDOWN_SECS: u64 = 15 * 60;
+
+/// Cooldown history for one host, as shown in [`HostCooldowns::report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CooldownReport {
+    pub host: HostKey,
+    /// Number of 429 responses that started or extended a cooldown.
+    pub incurred: u32,
+    /// Time left on the current cooldown, if one is active.
+    pub remaining: Option<Duration>,
+}
+
+/// A request refused because its host's cooldown outlasts the request timeout.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Host {host} is cooling down after HTTP 429 Too Many Requests")]
+pub struct CoolingDown {
+    pub host: HostKey,
+    pub remaining: Duration,
+}
+
+/// Shared per-host cooldown tracker.
+#[derive(Debug, Default)]
+pub struct HostCooldowns {
+    inner: Mutex<HashMap<HostKey, Entry>>,
+}
+
+#[derive(Debug, Default)]
+struct Entry {
+    until: Option<Instant>,
+    incurred: u32,
+}
+
+impl HostCooldowns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Time left before `host` may be contacted again.
+    pub fn remaining(&self, host: &HostKey) -> Option<Duration> {
+        time_left(self.inner.lock().get(host)?.until, Instant::now())
+    }
+
+    /// Fail with the time remaining if `host` is cooling down for at least
+    /// `deadline`, the verdict [`wait_for`](Self::wait_for) would reach
+    /// without waiting.
+    pub fn check(&self, host: &HostKey, deadline: Duration) -> Result<(), CoolingDown> {
+        match self.remaining(host) {
+            Some(left) if left >= deadline => Err(CoolingDown {
+                host: host.clone(),
+                remaining: left,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Wait out an active cooldown on `host` if it ends within `deadline`;
+    /// otherwise fail immediately with the time remaining.
+    ///
+    /// A 429 recorded while waiting extends the wait, still bounded by
+    /// `deadline`. Returns the time spent waiting, which callers subtract
+    /// from the request timeout.
+    pub async fn wait_for(&self, host: &HostKey, deadline: Duration) -> anyhow::Result<Duration> {
+        let started = Instant::now();
+        while let Some(left) = self.remaining(host) {
+            self.check(host, deadline.saturating_sub(started.elapsed()))?;
+            tokio::time::sleep(left).await;
+        }
+        Ok(started.elapsed())
+    }
+
+    /// Start, extend or clear the cooldown for `host` from a response.
+    pub fn record_response(&self, host: &HostKey, status: StatusCode, headers: &HeaderMap) {
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let secs = parse_retry_after_secs(headers)
+                .unwrap_or(DEFAULT_COOLDOWN_SECS)
+                .min(MAX_COOLDOWN_SECS);
+            let until = Instant::now() + Duration::from_secs(secs);
+            let mut inner = self.inner.lock();
+            let entry = inner.entry(host.clone()).or_default();
+            entry.incurred += 1;
+            entry.until = Some(entry.until.map_or(until, |current| current.max(until)));
+            tracing::warn!("{host} returned 429; cooling down for {secs}s");
+        } else if status.is_success() {
+            if let Some(entry) = self.inner.lock().get_mut(host) {
+                entry.until = None;
+            }
+        }
+    }
+
+    /// Cooldowns incurred per host, sorted by host.
+    pub fn report(&self) -> Vec<CooldownReport> {
+        let now = Instant::now();
+        let mut report: Vec<_> = self
+            .inner
+            .lock()
+            .iter()
+            .map(|(host, entry)| CooldownReport {
+                host: host.clone(),
+                incurred: entry.incurred,
+                remaining: time_left(entry.until, now),
+            })
+            .collect();
+        report.sort_by(|a, b| a.host.cmp(&b.host));
+        report
+    }
+
+    /// Hosts from [`report`](Self::report) with an active cooldown, one line
+    /// each, for tool output. Empty when no host is cooling down.
+    pub fn describe_active(&self) -> String {
+        self.report()
+            .iter()
+            .filter_map(|entry| {
+                let left = entry.remaining?;
+                Some(format!(
+                    "cooldown: {} for {}s more (HTTP 429 responses: {})",
+                    entry.host,
+                    left.as_secs().max(1),
+                    entry.incurred
+                ))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn time_left(until: Option<Instant>, now: Instant) -> Option<Duration> {
+    until?
+        .checked_duration_since(now)
+        .filter(|left| !left.is_zero())
+}
+
+/// Parse `Retry-After` as delta-seconds or an HTTP date.
+pub fn parse_retry_after_secs(headers: &HeaderMap) -> Option<u64> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(secs);
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delta = at.signed_duration_since(chrono::Utc::now()).num_seconds();
+    Some(u64::try_from(delta).unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn retry_after(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn parses_seconds_and_http_dates() {
+        assert_eq!(parse_retry_after_secs(&retry_after("120")), Some(120));
+        assert_eq!(
+            parse_retry_after_secs(&retry_after("Wed, 21 Oct 2015 07:28:00 GMT")),
+            Some(0)
+        );
+        let soon = (chrono::Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        let parsed = parse_retry_after_secs(&retry_after(&soon)).unwrap();
+        assert!((88..=90).contains(&parsed), "{parsed}");
+        assert_eq!(parse_retry_after_secs(&retry_after("soon")), None);
+        assert_eq!(parse_retry_after_secs(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn too_many_requests_starts_cooldown_and_success_clears_it() {
+        let cooldowns = HostCooldowns::new();
+        let host = HostKey::from("api.example.com");
+        let other = HostKey::from("other.example.com");
+
+        cooldowns.record_response(&host, StatusCode::TOO_MANY_REQUESTS, &retry_after("60"));
+        let left = cooldowns.remaining(&host).unwrap();
+        assert!(left > Duration::from_secs(58) && left <= Duration::from_secs(60));
+        assert_eq!(cooldowns.remaining(&other), None);
+
+        cooldowns.record_response(&host, StatusCode::NOT_FOUND, &HeaderMap::new());
+        assert!(cooldowns.remaining(&host).is_some());
+        cooldowns.record_response(&host, StatusCode::OK, &HeaderMap::new());
+        assert_eq!(cooldowns.remaining(&host), None);
+
+        assert_eq!(
+            cooldowns.report(),
+            vec![CooldownReport {
+                host,
+                incurred: 1,
+                remaining: None
+            }]
+        );
+    }
+
+    #[test]
+    fn describe_active_lists_only_hosts_still_cooling_down() {
+        let cooldowns = HostCooldowns::new();
+        let a = HostKey::from("a.example");
+        let b = HostKey::from("b.example");
+        assert_eq!(cooldowns.describe_active(), "");
+
+        cooldowns.record_response(&a, StatusCode::TOO_MANY_REQUESTS, &retry_after("60"));
+        cooldowns.record_response(&a, StatusCode::TOO_MANY_REQUESTS, &retry_after("60"));
+        cooldowns.record_response(&b, StatusCode::TOO_MANY_REQUESTS, &retry_after("60"));
+        cooldowns.record_response(&b, StatusCode::OK, &HeaderMap::new());
+
+        let described = cooldowns.describe_active();
+        assert!(
+            described == "cooldown: a.example for 60s more (HTTP 429 responses: 2)"
+                || described == "cooldown: a.example for 59s more (HTTP 429 responses: 2)",
+            "{described}"
+        );
+    }
+
+    #[test]
+    fn missing_or_huge_retry_after_is_bounded() {
+        let cooldowns = HostCooldowns::new();
+        let a = HostKey::from("a.example");
+        let b = HostKey::from("b.example");
+        cooldowns.record_response(&a, StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new());
+        cooldowns.record_response(&b, StatusCode::TOO_MANY_REQUESTS, &retry_after("999999"));
+
+        let a_left = cooldowns.remaining(&a).unwrap().as_secs();
+        let b_left = cooldowns.remaining(&b).unwrap().as_secs();
+        assert!((DEFAULT_COOLDOWN_SECS - 1..=DEFAULT_COOLDOWN_SECS).contains(&a_left));
+        assert!((MAX_COOLDOWN_SECS - 1..=MAX_COOLDOWN_SECS).contains(&b_left));
+    }
+
+    #[tokio::test]
+    async fn wait_for_sleeps_within_deadline_and_fails_fast_beyond_it() {
+        let cooldowns = HostCooldowns::new();
+        let host = HostKey::from("api.example.com");
+        cooldowns.record_response(&host, StatusCode::TOO_MANY_REQUESTS, &retry_after("1"));
+
+        let err = cooldowns
+            .wait_for(&host, Duration::from_millis(500))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("cooling down"), "{err}");
+        let cooling = err.downcast_ref::<CoolingDown>().unwrap();
+        assert!(cooling.remaining > Duration::from_millis(500));
+
+        let started = Instant::now();
+        let waited = cooldowns
+            .wait_for(&host, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert!(waited >= Duration::from_millis(500));
+        assert!(started.elapsed() >= waited);
+        assert_eq!(cooldowns.remaining(&host), None);
+    }
+
+    #[tokio::test]
+    async fn wait_for_rechecks_cooldowns_extended_while_waiting() {
+        let cooldowns = std::sync::Arc::new(HostCooldowns::new());
+        let host = HostKey::from("api.example.com");
+        cooldowns.record_response(&host, StatusCode::TOO_MANY_REQUESTS, &retry_after("1"));
+
+        // Another request sharing the tracker gets a fresh 429 mid-wait.
+        let extender = {
+            let cooldowns = cooldowns.clone();
+            let host = host.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                cooldowns.record_response(&host, StatusCode::TOO_MANY_REQUESTS, &retry_after("2"));
+            })
+        };
+
+        let started = Instant::now();
+        let err = cooldowns
+            .wait_for(&host, Duration::from_millis(1_500))
+            .await
+            .unwrap_err();
+        extender.await.unwrap();
+        assert!(err.downcast_ref::<CoolingDown>().is_some(), "{err}");
+        assert!(started.elapsed() < Duration::from_millis(1_500));
+        assert!(cooldowns.remaining(&host).is_some());
+    }
+
+    #[test]
+    fn check_matches_wait_for_without_waiting() {
+        let cooldowns = HostCooldowns::new();
+        let host = HostKey::from("api.example.com");
+        assert_eq!(cooldowns.check(&host, Duration::from_secs(1)), Ok(()));
+
+        cooldowns.record_response(&host, StatusCode::TOO_MANY_REQUESTS, &retry_after("60"));
+        assert_eq!(cooldowns.check(&host, Duration::from_secs(90)), Ok(()));
+        let cooling = cooldowns.check(&host, Duration::from_secs(30)).unwrap_err();
+        assert_eq!(cooling.host, host);
+        assert!(cooling.remaining > Duration::from_secs(58));
+    }
+}
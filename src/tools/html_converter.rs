@@ -0,0 +1,571 @@
+//! Pluggable HTML-to-text conversion for `web_fetch`.
+//!
+//! `web_fetch` owns fetching, URL policy and size limits; turning the fetched
+//! HTML into model-readable text is delegated to an [`HtmlConverter`].
+//! Embedders with their own readability pipeline can install theirs via
+//! [`WebFetchTool::with_html_converter`](super::WebFetchTool::with_html_converter).
+//!
+//! Without a custom converter, `web_fetch` feeds the body to a
+//! [`StreamingTextConverter`] chunk by chunk as it downloads. It runs the
+//! html5ever tokenizer without building a tree, so memory stays bounded by
+//! the output cap rather than the page size. A custom converter
+//! needs the whole document and gets the buffered path.
+//! [`DefaultHtmlConverter`] is the buffered form of the built-in conversion
+//! and produces the same text.
+
+use super::url_validation::ValidatedUrl;
+use html5ever::tendril::StrTendril;
+use html5ever::tokenizer::states::RawKind;
+use html5ever::tokenizer::{
+    BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer, TokenizerOpts,
+};
+use regex::Regex;
+use std::cell::{Cell, RefCell};
+use std::sync::LazyLock;
+
+/// Knobs passed to every conversion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConvertOptions {
+    /// Upper bound on `ConvertedDoc::text`, in characters. The tool truncates
+    /// anything longer, but converters should stop early rather than build
+    /// an unbounded string.
+    pub max_output_chars: usize,
+}
+
+/// Result of converting one HTML document.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConvertedDoc {
+    /// Readable text (plain text or markdown) returned to the model.
+    pub text: String,
+    /// Document title, if one was found.
+    pub title: Option<String>,
+    /// Absolute link targets found in the document, in document order.
+    pub links: Vec<String>,
+    /// Non-fatal problems worth surfacing to the operator (logged, not returned to the model).
+    pub warnings: Vec<String>,
+}
+
+/// Converts fetched HTML into a [`ConvertedDoc`].
+///
+/// Implementations must uphold these invariants:
+/// - **No panics.** Malformed or hostile markup must produce either a
+///   (possibly partial) document or an `Err`.
+/// - **Bounded output.** Respect `opts.max_output_chars`; input is already
+///   capped at the tool's `max_response_size`.
+/// - **No I/O.** Conversion runs inline on the fetch path and must not make
+///   network requests — `base_url` is for resolving relative links only.
+/// - **Absolute links.** Entries in `links` are resolved against `base_url`.
+pub trait HtmlConverter: Send + Sync {
+    fn convert(
+        &self,
+        html: &str,
+        base_url: &ValidatedUrl,
+        opts: &ConvertOptions,
+    ) -> anyhow::Result<ConvertedDoc>;
+}
+
+/// Built-in buffered converter: [`StreamingTextConverter`] text plus title
+/// and links.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultHtmlConverter;
+
+static TITLE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+static HREF_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)<a\s[^>]*href\s*=\s*["']([^"']+)["']"#).unwrap());
+
+impl HtmlConverter for DefaultHtmlConverter {
+    fn convert(
+        &self,
+        html: &str,
+        base_url: &ValidatedUrl,
+        opts: &ConvertOptions,
+    ) -> anyhow::Result<ConvertedDoc> {
+        let text = html_to_text(html, opts.max_output_chars);
+
+        let title = TITLE_RE
+            .captures(html)
+            .map(|caps| html_to_text(&caps[1], usize::MAX))
+            .filter(|title| !title.is_empty());
+
+        let base = reqwest::Url::parse(base_url.as_str()).ok();
+        let links = HREF_RE
+            .captures_iter(html)
+            .filter_map(|caps| match &base {
+                Some(base) => base.join(&caps[1]).ok().map(String::from),
+                None => reqwest::Url::parse(&caps[1]).ok().map(String::from),
+            })
+            .collect();
+
+        Ok(ConvertedDoc {
+            text,
+            title,
+            links,
+            warnings: Vec::new(),
+        })
+    }
+}
+
+/// One-shot conversion through [`StreamingTextConverter`].
+pub fn html_to_text(html: &str, max_output_chars: usize) -> String {
+    let mut converter = StreamingTextConverter::new(max_output_chars);
+    converter.push(html.as_bytes());
+    converter.finish()
+}
+
+/// Elements whose text is never rendered, with the tokenizer state their
+/// content is read in.
+const RAW_TEXT_ELEMENTS: &[(&str, RawKind)] = &[
+    ("script", RawKind::ScriptData),
+    ("style", RawKind::Rawtext),
+    ("noscript", RawKind::Rawtext),
+    ("iframe", RawKind::Rawtext),
+    ("noembed", RawKind::Rawtext),
+    ("noframes", RawKind::Rawtext),
+    ("xmp", RawKind::Rawtext),
+];
+/// Elements whose text is rendered but never contains markup.
+const RCDATA_ELEMENTS: &[&str] = &["title", "textarea"];
+/// Elements whose whole subtree is hidden, markup included.
+const HIDDEN_ELEMENTS: &[&str] = &["template", "svg"];
+/// Elements that start a new line in the rendered text.
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "td",
+    "th",
+    "title",
+    "tr",
+    "ul",
+];
+
+/// Incremental HTML-to-text converter for streamed bodies.
+///
+/// Bytes are pushed as they arrive and fed to the html5ever tokenizer; text
+/// is emitted from its tokens as they are produced and no DOM is built.
+/// Between chunks the converter carries only the tokenizer's state (the
+/// token in progress) and at most three bytes of a split UTF-8 sequence, so
+/// peak memory is the output cap plus the largest single token rather than
+/// the page size.
+///
+/// Links render as `text (href)`, or as the bare href when the anchor has no
+/// text of its own or its text is the href.
+///
+/// The tokenizer shares non-atomic tendrils, so the converter is `!Send`;
+/// async callers run it on a blocking thread and feed it over a channel.
+pub struct StreamingTextConverter {
+    tokenizer: Tokenizer<TextSink>,
+    input: BufferQueue,
+    /// Trailing bytes of an incomplete UTF-8 sequence.
+    pending: Vec<u8>,
+}
+
+impl StreamingTextConverter {
+    pub fn new(max_output_chars: usize) -> Self {
+        Self {
+            tokenizer: Tokenizer::new(TextSink::new(max_output_chars), TokenizerOpts::default()),
+            input: BufferQueue::default(),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Whether the output cap has been reached; callers can stop reading.
+    pub fn is_full(&self) -> bool {
+        self.tokenizer.sink.is_full()
+    }
+
+    /// Feed the next chunk of the body.
+    pub fn push(&mut self, chunk: &[u8]) {
+        if self.is_full() {
+            return;
+        }
+        let mut bytes = std::mem::take(&mut self.pending);
+        bytes.extend_from_slice(chunk);
+        let complete = complete_utf8_prefix(&bytes);
+        self.feed(&bytes[..complete]);
+        bytes.drain(..complete);
+        self.pending = bytes;
+    }
+
+    /// Flush whatever is left and return the converted text.
+    pub fn finish(mut self) -> String {
+        let pending = std::mem::take(&mut self.pending);
+        self.feed(&pending);
+        if !self.is_full() {
+            self.tokenizer.end();
+            self.tokenizer.sink.close_anchor();
+        }
+        let mut out = self.tokenizer.sink.out.take();
+        let trimmed_len = out.trim_end().len();
+        out.truncate(trimmed_len);
+        out
+    }
+
+    fn feed(&self, bytes: &[u8]) {
+        if bytes.is_empty() || self.is_full() {
+            return;
+        }
+        let text = String::from_utf8_lossy(bytes);
+        self.input.push_back(StrTendril::from_slice(&text));
+        // The sink never hands back a script, so this always runs to `Done`.
+        let _ = self.tokenizer.feed(&self.input);
+    }
+}
+
+/// Token sink that renders character tokens as whitespace-collapsed text.
+struct TextSink {
+    max_output_chars: usize,
+    out: RefCell<String>,
+    out_chars: Cell<usize>,
+    pending_space: Cell<bool>,
+    pending_newlines: Cell<usize>,
+    /// Inside an element from [`RAW_TEXT_ELEMENTS`].
+    in_raw_text: Cell<bool>,
+    /// Open elements from [`HIDDEN_ELEMENTS`].
+    hidden_depth: Cell<usize>,
+    /// Target of the open `<a>` and the output length where its text starts.
+    anchor: RefCell<Option<(String, usize)>>,
+}
+
+impl TextSink {
+    fn new(max_output_chars: usize) -> Self {
+        Self {
+            max_output_chars,
+            out: RefCell::new(String::new()),
+            out_chars: Cell::new(0),
+            pending_space: Cell::new(false),
+            pending_newlines: Cell::new(0),
+            in_raw_text: Cell::new(false),
+            hidden_depth: Cell::new(0),
+            anchor: RefCell::new(None),
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.out_chars.get() >= self.max_output_chars
+    }
+
+    fn handle_tag(&self, tag: &Tag) -> TokenSinkResult<()> {
+        let name: &str = &tag.name;
+        if tag.kind == TagKind::EndTag {
+            // The tokenizer only leaves a raw-text state on the matching
+            // end tag, so any end tag seen while inside one closes it.
+            self.in_raw_text.set(false);
+            if HIDDEN_ELEMENTS.contains(&name) {
+                self.hidden_depth
+                    .set(self.hidden_depth.get().saturating_sub(1));
+            }
+            if name == "a" {
+                self.close_anchor();
+            }
+        } else {
+            if let Some((_, kind)) = RAW_TEXT_ELEMENTS.iter().find(|(raw, _)| *raw == name) {
+                self.in_raw_text.set(true);
+                return TokenSinkResult::RawData(*kind);
+            }
+            if HIDDEN_ELEMENTS.contains(&name) && !tag.self_closing {
+                self.hidden_depth.set(self.hidden_depth.get() + 1);
+            }
+            if name == "a" {
+                self.open_anchor(tag);
+            }
+        }
+        if BLOCK_ELEMENTS.contains(&name) {
+            let wanted = if matches!(name, "p" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6") {
+                2
+            } else {
+                1
+            };
+            self.pending_newlines
+                .set(self.pending_newlines.get().max(wanted));
+            self.pending_space.set(false);
+        }
+        if tag.kind == TagKind::StartTag && RCDATA_ELEMENTS.contains(&name) {
+            return TokenSinkResult::RawData(RawKind::Rcdata);
+        }
+        TokenSinkResult::Continue
+    }
+
+    fn open_anchor(&self, tag: &Tag) {
+        self.close_anchor();
+        if self.hidden_depth.get() > 0 {
+            return;
+        }
+        let href = tag
+            .attrs
+            .iter()
+            .find(|attr| &*attr.name.local == "href")
+            .map(|attr| attr.value.trim())
+            .filter(|href| !href.is_empty() && !href.starts_with("javascript:"));
+        if let Some(href) = href {
+            *self.anchor.borrow_mut() = Some((href.to_string(), self.out.borrow().len()));
+        }
+    }
+
+    fn close_anchor(&self) {
+        let Some((href, start)) = self.anchor.borrow_mut().take() else {
+            return;
+        };
+        let same_as_text = {
+            let out = self.out.borrow();
+            let text = out.get(start..).unwrap_or_default().trim();
+            if text.is_empty() {
+                None
+            } else {
+                Some(text == href)
+            }
+        };
+        match same_as_text {
+            Some(true) => {}
+            Some(false) => {
+                if self.pending_newlines.get() == 0 {
+                    self.pending_space.set(true);
+                }
+                self.emit_text(&format!("({href})"));
+            }
+            None => self.emit_text(&href),
+        }
+    }
+
+    fn emit_text(&self, text: &str) {
+        if self.in_raw_text.get() || self.hidden_depth.get() > 0 {
+            return;
+        }
+        let mut out = self.out.borrow_mut();
+        for ch in text.chars() {
+            if ch.is_whitespace() || ch == '\u{a0}' {
+                if self.pending_newlines.get() == 0 {
+                    self.pending_space.set(true);
+                }
+                continue;
+            }
+            if !out.is_empty() {
+                if self.pending_newlines.get() > 0 {
+                    for _ in 0..self.pending_newlines.get() {
+                        self.push_char(&mut out, '\n');
+                    }
+                } else if self.pending_space.get() {
+                    self.push_char(&mut out, ' ');
+                }
+            }
+            self.pending_newlines.set(0);
+            self.pending_space.set(false);
+            self.push_char(&mut out, ch);
+        }
+    }
+
+    fn push_char(&self, out: &mut String, ch: char) {
+        if self.is_full() {
+            return;
+        }
+        out.push(ch);
+        self.out_chars.set(self.out_chars.get() + 1);
+    }
+}
+
+impl TokenSink for TextSink {
+    type Handle = ();
+
+    fn process_token(&self, token: Token, _line_number: u64) -> TokenSinkResult<()> {
+        match token {
+            Token::TagToken(tag) => self.handle_tag(&tag),
+            Token::CharacterTokens(text) => {
+                self.emit_text(&text);
+                TokenSinkResult::Continue
+            }
+            _ => TokenSinkResult::Continue,
+        }
+    }
+}
+
+/// Length of `bytes` without a trailing incomplete UTF-8 sequence, which is
+/// held back until the rest of it arrives.
+fn complete_utf8_prefix(bytes: &[u8]) -> usize {
+    let len = bytes.len();
+    for back in 1..=len.min(3) {
+        let byte = bytes[len - back];
+        if byte & 0xC0 == 0x80 {
+            continue;
+        }
+        let width = match byte {
+            b if b & 0xE0 == 0xC0 => 2,
+            b if b & 0xF0 == 0xE0 => 3,
+            b if b & 0xF8 == 0xF0 => 4,
+            _ => 1,
+        };
+        return if width > back { len - back } else { len };
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::url_validation::{SchemeConstraint, UrlPolicy};
+
+    fn base(url: &str) -> ValidatedUrl {
+        UrlPolicy::new(SchemeConstraint::HttpOrHttps, vec!["*".into()])
+            .validate(url)
+            .unwrap()
+    }
+
+    #[test]
+    fn default_converter_extracts_text_title_and_links() {
+        let html = r#"<html><head><title>Example Docs</title></head>
+            <body><p>Hello</p><a href="/a">A</a><a class="x" href='https://other.example/b'>B</a></body></html>"#;
+        let doc = DefaultHtmlConverter
+            .convert(
+                html,
+                &base("https://example.com/docs/index.html"),
+                &ConvertOptions {
+                    max_output_chars: 10_000,
+                },
+            )
+            .unwrap();
+        assert!(doc.text.contains("Hello"));
+        assert_eq!(doc.title.as_deref(), Some("Example Docs"));
+        assert_eq!(
+            doc.links,
+            vec!["https://example.com/a", "https://other.example/b"]
+        );
+        assert!(doc.warnings.is_empty());
+    }
+
+    #[test]
+    fn default_converter_respects_output_limit() {
+        let doc = DefaultHtmlConverter
+            .convert(
+                "<p>ééééé ééééé</p>",
+                &base("https://example.com/"),
+                &ConvertOptions {
+                    max_output_chars: 3,
+                },
+            )
+            .unwrap();
+        assert_eq!(doc.text.chars().count(), 3);
+    }
+
+    const FIXTURE: &str = "<!DOCTYPE html><html><head><title>T &amp; C</title>\
+        <style>p { color: red; }</style><script>if (a < b) { x = '</p>'; }</script></head>\
+        <body><h1>Caf\u{e9} &lt;menu&gt;</h1><!-- a <b>hidden</b> comment -->\
+        <p>Line&nbsp;one &#8212; two&#x21;</p><div>\u{65e5}\u{672c}\u{8a9e}   text\n  wraps</div>\
+        <ul><li>first</li><li>second &unknown; &amp</li></ul></body></html>";
+
+    #[test]
+    fn streaming_converter_renders_text_blocks_and_entities() {
+        let text = html_to_text(FIXTURE, 10_000);
+        assert_eq!(
+            text,
+            "T & C\n\nCaf\u{e9} <menu>\n\nLine one \u{2014} two!\n\n\u{65e5}\u{672c}\u{8a9e} text wraps\nfirst\nsecond &unknown; &"
+        );
+    }
+
+    #[test]
+    fn streaming_output_is_independent_of_chunk_boundaries() {
+        let expected = html_to_text(FIXTURE, 10_000);
+        for chunk_size in [1, 2, 3, 5, 7, 64] {
+            let mut converter = StreamingTextConverter::new(10_000);
+            for chunk in FIXTURE.as_bytes().chunks(chunk_size) {
+                converter.push(chunk);
+            }
+            assert_eq!(converter.finish(), expected, "chunk size {chunk_size}");
+        }
+    }
+
+    #[test]
+    fn streaming_converter_skips_long_attribute_values() {
+        let html = format!(
+            "<p>before</p><div data-x=\"{}\">after</div>",
+            "y".repeat(100_000)
+        );
+        let mut converter = StreamingTextConverter::new(1_000);
+        for chunk in html.as_bytes().chunks(4096) {
+            converter.push(chunk);
+        }
+        assert_eq!(converter.finish(), "before\n\nafter");
+    }
+
+    #[test]
+    fn bare_less_than_is_text() {
+        assert_eq!(
+            html_to_text("<p>1 < 2 and 3 <= 4</p><p>next</p>", 1_000),
+            "1 < 2 and 3 <= 4\n\nnext"
+        );
+    }
+
+    #[test]
+    fn greater_than_inside_quoted_attribute_does_not_close_tag() {
+        assert_eq!(
+            html_to_text(
+                "<a title=\"x > y\" data-q='a>b'>link</a> tail<img alt=\">\">",
+                1_000
+            ),
+            "link tail"
+        );
+    }
+
+    #[test]
+    fn links_render_with_their_targets() {
+        assert_eq!(
+            html_to_text("click <a class=\"x\" href=\"test\">here</a> now", 1_000),
+            "click here (test) now"
+        );
+        assert_eq!(
+            html_to_text(
+                "<a href=\"https://a.example/\">https://a.example/</a>",
+                1_000
+            ),
+            "https://a.example/"
+        );
+        assert_eq!(
+            html_to_text("see <a href=\"/img\"><img alt=\"\"></a>", 1_000),
+            "see /img"
+        );
+        assert_eq!(
+            html_to_text(
+                "<a href=\"ents/&apos;x&apos;\"><span>here</span> or here</a>",
+                1_000
+            ),
+            "here or here (ents/'x')"
+        );
+        assert_eq!(
+            html_to_text("click <a href=\"javascript:void(0)\">here</a>", 1_000),
+            "click here"
+        );
+    }
+
+    #[test]
+    fn links_inside_hidden_elements_are_not_rendered() {
+        assert_eq!(
+            html_to_text("<svg><a href=\"/x\">icon</a></svg>text", 1_000),
+            "text"
+        );
+    }
+}
@@ -1,7 +1,19 @@
-use super::traits::{Tool, ToolResult};
-use crate::security::SecurityPolicy;
+use super::header_order::{canonicalize_headers, casing_domains};
+use super::host_cooldown::HostCooldowns;
+use super::pre_send::{PreSendInterceptor, intercept_request};
+use super::response_body::{
+    EmptyBody, TruncatedTransfer, body_text, mime_essence, read_body, sniff_content_type,
+};
+use super::retry_guidance::{RetryGuidance, SuggestedAction, rate_limited_error, read_only_error};
+use super::traits::{CostEstimate, Refusal, ShortCircuit, Tool, ToolResult};
+use super::url_validation::{
+    HostKey, SchemeConstraint, UrlPolicy, UrlValidationError, ValidatedUrl, host_key_matches,
+    normalize_domain, redact_url_fragments,
+};
+use crate::security::{AutonomyLevel, SecurityPolicy};
 use async_trait::async_trait;
 use serde_json::json;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -9,10 +21,17 @@ use std::time::Duration;
 /// Supports GET, POST, PUT, DELETE methods with configurable security.
 pub struct HttpRequestTool {
     security: Arc<SecurityPolicy>,
-    allowed_domains: Vec<String>,
+    policy: UrlPolicy,
     max_response_size: usize,
     timeout_secs: u64,
-    allow_private_hosts: bool,
+    interceptor: Option<Arc<dyn PreSendInterceptor>>,
+    /// Per-domain accepted response types (lowercase media types, `type/*` allowed).
+    expected_content_types: Vec<(HostKey, Vec<String>)>,
+    cooldowns: Arc<HostCooldowns>,
+    /// Hosts sent `Title-Case` header names.
+    browser_like_casing: Vec<HostKey>,
+    /// Keep URL fragments in error text (debug override).
+    log_fragments: bool,
 }
 
 impl HttpRequestTool {
@@ -25,45 +44,168 @@ impl HttpRequestTool {
     ) -> Self {
         Self {
             security,
-            allowed_domains: normalize_allowed_domains(allowed_domains),
+            policy: UrlPolicy::new(SchemeConstraint::HttpOrHttps, allowed_domains)
+                .with_allow_private_hosts(allow_private_hosts),
             max_response_size,
             timeout_secs,
-            allow_private_hosts,
+            interceptor: None,
+            expected_content_types: Vec::new(),
+            cooldowns: Arc::new(HostCooldowns::new()),
+            browser_like_casing: Vec::new(),
+            log_fragments: false,
         }
     }
 
-    fn validate_url(&self, raw_url: &str) -> anyhow::Result<String> {
-        let url = raw_url.trim();
+    /// Only accept the listed response content types from these domains.
+    pub fn with_expected_content_types(mut self, by_domain: HashMap<String, Vec<String>>) -> Self {
+        self.expected_content_types = by_domain
+            .into_iter()
+            .filter_map(|(domain, types)| {
+                let domain = HostKey::from(normalize_domain(&domain)?);
+                let types = types
+                    .iter()
+                    .map(|t| mime_essence(t))
+                    .filter(|t| !t.is_empty())
+                    .collect();
+                Some((domain, types))
+            })
+            .collect();
+        self
+    }
 
-        if url.is_empty() {
-            anyhow::bail!("URL cannot be empty");
-        }
+    /// Let an embedder inspect, veto or tag each request just before it is sent.
+    pub fn with_pre_send_interceptor(mut self, interceptor: Arc<dyn PreSendInterceptor>) -> Self {
+        self.interceptor = Some(interceptor);
+        self
+    }
 
-        if url.chars().any(char::is_whitespace) {
-            anyhow::bail!("URL cannot contain whitespace");
-        }
+    /// Send `Title-Case` header names to these domains and their subdomains.
+    /// Other hosts get lowercase names; the casing passed in `headers` is
+    /// not preserved.
+    pub fn with_browser_like_casing(mut self, domains: Vec<String>) -> Self {
+        self.browser_like_casing = casing_domains(&domains);
+        self
+    }
 
-        if !url.starts_with("http://") && !url.starts_with("https://") {
-            anyhow::bail!("Only http:// and https:// URLs are allowed");
-        }
+    /// Share 429 cooldowns with other network tools.
+    pub fn with_host_cooldowns(mut self, cooldowns: Arc<HostCooldowns>) -> Self {
+        self.cooldowns = cooldowns;
+        self
+    }
 
-        if self.allowed_domains.is_empty() {
-            anyhow::bail!(
-                "HTTP request tool is enabled but no allowed_domains are configured. Add [http_request].allowed_domains in config.toml"
-            );
-        }
+    /// Keep URL fragments in errors instead of redacting them
+    /// (`[security.audit].log_fragments`).
+    pub fn with_log_fragments(mut self, log_fragments: bool) -> Self {
+        self.log_fragments = log_fragments;
+        self
+    }
+
+    fn validate_url(&self, raw_url: &str) -> Result<ValidatedUrl, UrlValidationError> {
+        self.policy.validate(raw_url)
+    }
+
+    /// Whether an operator is in the loop to extend `allowed_domains`.
+    fn approval_enabled(&self) -> bool {
+        self.security.autonomy == AutonomyLevel::Supervised
+    }
 
-        let host = extract_host(url)?;
+    /// Retry guidance for a failed send, consistent with
+    /// [`url_error_message`](Self::url_error_message) for policy failures.
+    fn error_guidance(&self, err: &anyhow::Error) -> RetryGuidance {
+        RetryGuidance::for_error(err, self.approval_enabled())
+    }
 
-        if !self.allow_private_hosts && is_private_or_local_host(&host) {
-            anyhow::bail!("Blocked local/private host: {host}");
+    /// [`describe_url_error`] with retry guidance. A host outside the
+    /// allowlist points at the operator in supervised mode.
+    fn url_error_message(&self, err: &UrlValidationError) -> String {
+        RetryGuidance::for_url_error(err, self.approval_enabled())
+            .append_to(&describe_url_error(err))
+    }
+
+    /// [`url_error_message`](Self::url_error_message) as a preflight refusal.
+    fn url_refusal(&self, err: &UrlValidationError) -> Refusal {
+        let guidance = RetryGuidance::for_url_error(err, self.approval_enabled());
+        Refusal {
+            needs_approval: guidance.suggested_action == SuggestedAction::AskOperatorForApproval,
+            ..self.url_error_message(err).into()
         }
+    }
 
-        if !host_matches_allowlist(&host, &self.allowed_domains) {
-            anyhow::bail!("Host '{host}' is not in http_request.allowed_domains");
+    /// Checks shared by [`estimate`](Self::estimate) and `execute`: the `url`
+    /// argument, autonomy, the action budget, the offline URL policy, the
+    /// method and a 429 cooldown on the host that outlasts the request
+    /// timeout.
+    ///
+    /// The budget is only inspected here; `execute` records the action
+    /// afterwards. Errors carry retry guidance.
+    fn preflight(&self, args: &serde_json::Value) -> Result<ValidatedUrl, Refusal> {
+        let url = args
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| RetryGuidance::DO_NOT_RETRY.append_to("Missing 'url' parameter"))?;
+        let method = args.get("method").and_then(|v| v.as_str()).unwrap_or("GET");
+        if !self.security.can_act() {
+            return Err(read_only_error().into());
+        }
+        if self.security.is_rate_limited() {
+            return Err(rate_limited_error().into());
+        }
+        let url = self
+            .policy
+            .validate_offline(url)
+            .map_err(|e| self.url_refusal(&e))?;
+        self.validate_method(method)
+            .map_err(|e| RetryGuidance::DO_NOT_RETRY.append_to(&e.to_string()))?;
+        if let Err(cooling) = self.cooldowns.check(url.host_key(), self.request_timeout()) {
+            let short_circuit = ShortCircuit::Cooldown {
+                remaining: cooling.remaining,
+            };
+            let err = anyhow::Error::new(cooling);
+            return Err(Refusal {
+                short_circuit: Some(short_circuit),
+                ..self
+                    .error_guidance(&err)
+                    .append_to(&format!("HTTP request failed: {err}"))
+                    .into()
+            });
+        }
+        Ok(url)
+    }
+
+    /// Estimate what `execute(args)` would cost without touching the network.
+    ///
+    /// Runs the same [`preflight`](Self::preflight) as execution, which skips
+    /// the DNS lookup. Redirects are not followed, so a call is always a
+    /// single request. The byte bound is `None` only when
+    /// `max_response_size` is 0, which disables the limit.
+    pub fn estimate(&self, args: &serde_json::Value) -> CostEstimate {
+        if let Err(refusal) = self.preflight(args) {
+            return refusal.into();
         }
 
-        Ok(url.to_string())
+        CostEstimate {
+            request_count: 1,
+            max_request_count: 1,
+            worst_case_bytes: self
+                .body_limit()
+                .map(|limit| limit.saturating_add(1) as u64),
+            blocked: None,
+            may_prompt_approval: false,
+            short_circuit: None,
+        }
+    }
+
+    /// Bytes of body kept for the output; `None` when unlimited.
+    fn body_limit(&self) -> Option<usize> {
+        (self.max_response_size > 0).then_some(self.max_response_size)
+    }
+
+    fn request_timeout(&self) -> Duration {
+        Duration::from_secs(if self.timeout_secs == 0 {
+            30
+        } else {
+            self.timeout_secs
+        })
     }
 
     fn validate_method(&self, method: &str) -> anyhow::Result<reqwest::Method> {
@@ -114,25 +256,26 @@ impl HttpRequestTool {
 
     async fn execute_request(
         &self,
-        url: &str,
+        url: &ValidatedUrl,
         method: reqwest::Method,
         headers: Vec<(String, String)>,
         body: Option<&str>,
     ) -> anyhow::Result<reqwest::Response> {
-        let timeout_secs = if self.timeout_secs == 0 {
+        if self.timeout_secs == 0 {
             tracing::warn!("http_request: timeout_secs is 0, using safe default of 30s");
-            30
-        } else {
-            self.timeout_secs
-        };
-        let builder = reqwest::Client::builder()
-            .timeout(Duration::from_secs(timeout_secs))
+        }
+        let timeout = self.request_timeout();
+        let mut builder = reqwest::Client::builder()
+            .timeout(timeout)
             .connect_timeout(Duration::from_secs(10))
             .redirect(reqwest::redirect::Policy::none());
+        if host_key_matches(url.host_key(), &self.browser_like_casing) {
+            builder = builder.http1_title_case_headers();
+        }
         let builder = crate::config::apply_runtime_proxy_to_builder(builder, "tool.http_request");
         let client = builder.build()?;
 
-        let mut request = client.request(method, url);
+        let mut request = client.request(method, url.as_str());
 
         for (key, value) in headers {
             request = request.header(&key, &value);
@@ -142,7 +285,90 @@ impl HttpRequestTool {
             request = request.body(body_str.to_string());
         }
 
-        Ok(request.send().await?)
+        let waited = self.cooldowns.wait_for(url.host_key(), timeout).await?;
+        // Time spent cooling down comes out of the request timeout.
+        let timeout = timeout.saturating_sub(waited);
+        let mut request = request.build()?;
+        canonicalize_headers(&mut request);
+        *request.timeout_mut() = Some(timeout);
+        if let Some(interceptor) = &self.interceptor {
+            intercept_request(
+                interceptor.as_ref(),
+                "http_request",
+                &mut request,
+                timeout,
+                self.log_fragments,
+            )?;
+        }
+
+        let response = client.execute(request).await?;
+        self.cooldowns
+            .record_response(url.host_key(), response.status(), response.headers());
+        Ok(response)
+    }
+
+    /// Replace the body with an `unexpected_content_type` outcome when `host`
+    /// has expected content types and the response matches none of them.
+    ///
+    /// Both the declared type and, when the body has a recognizable
+    /// signature, the sniffed type must be expected; a response with neither
+    /// is withheld too.
+    fn withhold_unexpected_body(
+        &self,
+        host: &HostKey,
+        status: u16,
+        declared: &str,
+        body: &[u8],
+    ) -> Option<ToolResult> {
+        let (_, expected) = self
+            .expected_content_types
+            .iter()
+            .filter(|(domain, _)| host.is_within(domain))
+            .max_by_key(|(domain, _)| domain.as_str().len())?;
+
+        let declared_essence = mime_essence(declared);
+        let sniffed = sniff_content_type(body);
+        let is_expected = |mime: &str| {
+            expected.iter().any(|e| {
+                e == mime
+                    || e.strip_suffix("/*")
+                        .is_some_and(|major| mime.split('/').next() == Some(major))
+            })
+        };
+        let declared_ok = if declared_essence.is_empty() {
+            sniffed.is_some()
+        } else {
+            is_expected(&declared_essence)
+        };
+        if declared_ok && sniffed.is_none_or(is_expected) {
+            return None;
+        }
+
+        let got_html = declared_essence == "text/html" || sniffed == Some("text/html");
+        let mut outcome = json!({
+            "outcome": "unexpected_content_type",
+            "status": status,
+            "declared_content_type": declared,
+            "sniffed_content_type": sniffed,
+            "expected_content_types": expected,
+        });
+        if got_html {
+            outcome["hint"] = json!(
+                "An HTML page where an API response was expected usually means a login or SSO \
+                 redirect; the credentials for this domain may have expired."
+            );
+        }
+
+        Some(ToolResult {
+            success: false,
+            output: outcome.to_string(),
+            error: Some(RetryGuidance::DO_NOT_RETRY.append_to(&format!(
+                "Unexpected content type from {host}: declared '{declared}', sniffed '{}', \
+                 expected {}. The response body was withheld",
+                sniffed.unwrap_or("unknown"),
+                expected.join(", ")
+            ))),
+        })
     }
 
     fn truncate_response(&self, text: &str) -> String {
@@ -189,7 +415,7 @@ impl Tool for HttpRequestTool {
                 },
                 "headers": {
                     "type": "object",
-                    "description": "Optional HTTP headers as key-value pairs (e.g., {\"Authorization\": \"Bearer token\", \"Content-Type\": \"application/json\"})",
+                    "description": "Optional HTTP headers as key-value pairs (e.g., {\"Authorization\": \"Bearer token\", \"Content-Type\": \"application/json\"}). Names are sent lowercase (or Title-Case for configured hosts), not in the casing given here",
                     "default": {}
                 },
                 "body": {
@@ -202,54 +428,49 @@ impl Tool for HttpRequestTool {
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
-        let url = args
-            .get("url")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Missing 'url' parameter"))?;
+        let url = match self.preflight(&args) {
+            Ok(url) => url,
+            Err(refusal) => {
+                let output = if refusal.short_circuit.is_some() {
+                    self.cooldowns.describe_active()
+                } else {
+                    String::new()
+                };
+                return Ok(ToolResult {
+                    success: false,
+                    output,
+                    error: Some(refusal.message),
+                });
+            }
+        };
 
         let method_str = args.get("method").and_then(|v| v.as_str()).unwrap_or("GET");
         let headers_val = args.get("headers").cloned().unwrap_or(json!({}));
         let body = args.get("body").and_then(|v| v.as_str());
 
-        if !self.security.can_act() {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some("Action blocked: autonomy is read-only".into()),
-            });
-        }
-
         if !self.security.record_action() {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Action blocked: rate limit exceeded".into()),
+                error: Some(rate_limited_error()),
             });
         }
 
-        let url = match self.validate_url(url) {
+        let url = match self.validate_url(url.as_str()) {
             Ok(v) => v,
             Err(e) => {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(e.to_string()),
-                });
-            }
-        };
-
-        let method = match self.validate_method(method_str) {
-            Ok(m) => m,
-            Err(e) => {
-                return Ok(ToolResult {
-                    success: false,
-                    output: String::new(),
-                    error: Some(e.to_string()),
+                    error: Some(self.url_error_message(&e)),
                 });
             }
         };
+        // Already accepted by `preflight`.
+        let method = self.validate_method(method_str)?;
 
         let request_headers = self.parse_headers(&headers_val);
+        let idempotent = method.is_idempotent();
 
         match self
             .execute_request(&url, method, request_headers, body)
@@ -273,10 +494,48 @@ impl Tool for HttpRequestTool {
                     .collect::<Vec<_>>()
                     .join(", ");
 
+                let declared_type = response
+                    .headers()
+                    .get(reqwest::header::CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or("")
+                    .to_string();
+                let guidance = RetryGuidance::for_status(status, response.headers());
+
                 // Get response body with size limit
-                let response_text = match response.text().await {
-                    Ok(text) => self.truncate_response(&text),
-                    Err(e) => format!("[Failed to read response body: {e}]"),
+                let (bytes, truncated) = read_body(response, self.body_limit()).await;
+                let empty = EmptyBody::classify(status_code, &bytes);
+                // Partial bodies go through the content-type check too, so a
+                // truncated login page is withheld like a complete one.
+                if let Some(withheld) = self
+                    .withhold_unexpected_body(url.host_key(), status_code, &declared_type, &bytes)
+                    .filter(|_| empty.is_none())
+                {
+                    return Ok(withheld);
+                }
+                // A partial body is withheld, as in web_fetch, so it cannot be
+                // mistaken for the whole response.
+                if let Some(short) = truncated {
+                    return Ok(ToolResult {
+                        success: false,
+                        output: format!(
+                            "Status: {} {}\nResponse Headers: {}\n\n{}",
+                            status_code,
+                            status.canonical_reason().unwrap_or("Unknown"),
+                            headers_text,
+                            short.marker()
+                        ),
+                        error: Some(truncated_transfer_error(
+                            short,
+                            idempotent,
+                            &declared_type,
+                            &bytes,
+                        )),
+                    });
+                }
+                let response_text = match empty {
+                    Some(empty) => empty.describe(status_code),
+                    None => self.truncate_response(&body_text(&bytes)),
                 };
 
                 let output = format!(
@@ -286,177 +545,92 @@ impl Tool for HttpRequestTool {
                     headers_text,
                     response_text
                 );
+                let output = if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                    format!("{output}\n\n{}", self.cooldowns.describe_active())
+                } else {
+                    output
+                };
 
                 Ok(ToolResult {
                     success: status.is_success(),
                     output,
                     error: if status.is_client_error() || status.is_server_error() {
-                        Some(format!("HTTP {}", status_code))
+                        Some(guidance.append_to(&format!("HTTP {status_code}")))
                     } else {
                         None
                     },
                 })
             }
-            Err(e) => Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some(format!("HTTP request failed: {e}")),
-            }),
+            Err(e) => {
+                let message = format!("HTTP request failed: {e}");
+                let message = if self.log_fragments {
+                    message
+                } else {
+                    redact_url_fragments(&message).into_owned()
+                };
+                Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(self.error_guidance(&e).append_to(&message)),
+                })
+            }
         }
     }
 }
 
-// Helper functions similar to browser_open.rs
-
-fn normalize_allowed_domains(domains: Vec<String>) -> Vec<String> {
-    let mut normalized = domains
-        .into_iter()
-        .filter_map(|d| normalize_domain(&d))
-        .collect::<Vec<_>>();
-    normalized.sort_unstable();
-    normalized.dedup();
-    normalized
-}
-
-fn normalize_domain(raw: &str) -> Option<String> {
-    let mut d = raw.trim().to_lowercase();
-    if d.is_empty() {
-        return None;
-    }
-
-    if let Some(stripped) = d.strip_prefix("https://") {
-        d = stripped.to_string();
-    } else if let Some(stripped) = d.strip_prefix("http://") {
-        d = stripped.to_string();
-    }
-
-    if let Some((host, _)) = d.split_once('/') {
-        d = host.to_string();
-    }
-
-    d = d.trim_start_matches('.').trim_end_matches('.').to_string();
-
-    if let Some((host, _)) = d.split_once(':') {
-        d = host.to_string();
-    }
-
-    if d.is_empty() || d.chars().any(char::is_whitespace) {
-        return None;
-    }
-
-    Some(d)
-}
-
-fn extract_host(url: &str) -> anyhow::Result<String> {
-    let rest = url
-        .strip_prefix("http://")
-        .or_else(|| url.strip_prefix("https://"))
-        .ok_or_else(|| anyhow::anyhow!("Only http:// and https:// URLs are allowed"))?;
-
-    let authority = rest
-        .split(['/', '?', '#'])
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("Invalid URL"))?;
-
-    if authority.is_empty() {
-        anyhow::bail!("URL must include a host");
-    }
-
-    if authority.contains('@') {
-        anyhow::bail!("URL userinfo is not allowed");
-    }
-
-    if authority.starts_with('[') {
-        anyhow::bail!("IPv6 hosts are not supported in http_request");
-    }
-
-    let host = authority
-        .split(':')
-        .next()
-        .unwrap_or_default()
-        .trim()
-        .trim_end_matches('.')
-        .to_lowercase();
-
-    if host.is_empty() {
-        anyhow::bail!("URL must include a valid host");
-    }
-
-    Ok(host)
+/// Error for a body that ended early. Only idempotent requests are worth
+/// repeating, and a partial JSON body that fails to parse is blamed on the
+/// truncation rather than on the server.
+fn truncated_transfer_error(
+    short: TruncatedTransfer,
+    idempotent: bool,
+    declared_type: &str,
+    partial: &[u8],
+) -> String {
+    let mut message = short.to_string();
+    let essence = mime_essence(declared_type);
+    let is_json = essence == "application/json" || essence.ends_with("+json");
+    if is_json && serde_json::from_slice::<serde_json::Value>(partial).is_err() {
+        message.push_str("; the partial body is not valid JSON because of this");
+    }
+    let guidance = if idempotent {
+        RetryGuidance::wait_and_retry(None)
+    } else {
+        RetryGuidance::DO_NOT_RETRY
+    };
+    guidance.append_to(&message)
 }
 
-fn host_matches_allowlist(host: &str, allowed_domains: &[String]) -> bool {
-    if allowed_domains.iter().any(|domain| domain == "*") {
-        return true;
-    }
-
-    allowed_domains.iter().any(|domain| {
-        host == domain
-            || host
-                .strip_suffix(domain)
-                .is_some_and(|prefix| prefix.ends_with('.'))
-    })
-}
-
-fn is_private_or_local_host(host: &str) -> bool {
-    // Strip brackets from IPv6 addresses like [::1]
-    let bare = host
-        .strip_prefix('[')
-        .and_then(|h| h.strip_suffix(']'))
-        .unwrap_or(host);
-
-    let has_local_tld = bare
-        .rsplit('.')
-        .next()
-        .is_some_and(|label| label == "local");
-
-    if bare == "localhost" || bare.ends_with(".localhost") || has_local_tld {
-        return true;
-    }
-
-    if let Ok(ip) = bare.parse::<std::net::IpAddr>() {
-        return match ip {
-            std::net::IpAddr::V4(v4) => is_non_global_v4(v4),
-            std::net::IpAddr::V6(v6) => is_non_global_v6(v6),
-        };
+/// Map a validation failure to the operator-facing message for this tool.
+fn describe_url_error(err: &UrlValidationError) -> String {
+    match err {
+        UrlValidationError::NoAllowlistConfigured => {
+            "HTTP request tool is enabled but no allowed_domains are configured. \
+             Add [http_request].allowed_domains in config.toml"
+                .into()
+        }
+        UrlValidationError::NotInAllowlist { host } => format!(
+            "Host '{host}' is not in http_request.allowed_domains. \
+             Add this domain to [http_request].allowed_domains in config.toml to allow it"
+        ),
+        UrlValidationError::PrivateOrLocalHost { host } => format!(
+            "Blocked local/private host: {host}. \
+             Set [http_request].allow_private_hosts = true in config.toml to allow private hosts"
+        ),
+        UrlValidationError::Ipv6NotSupported => {
+            "IPv6 hosts are not supported in http_request".into()
+        }
+        other => other.to_string(),
     }
-
-    false
-}
-
-/// Returns true if the IPv4 address is not globally routable.
-fn is_non_global_v4(v4: std::net::Ipv4Addr) -> bool {
-    let [a, b, c, _] = v4.octets();
-    v4.is_loopback()                       // 127.0.0.0/8
-        || v4.is_private()                 // 10/8, 172.16/12, 192.168/16
-        || v4.is_link_local()              // 169.254.0.0/16
-        || v4.is_unspecified()             // 0.0.0.0
-        || v4.is_broadcast()              // 255.255.255.255
-        || v4.is_multicast()              // 224.0.0.0/4
-        || (a == 100 && (64..=127).contains(&b)) // Shared address space (RFC 6598)
-        || a >= 240                        // Reserved (240.0.0.0/4, except broadcast)
-        || (a == 192 && b == 0 && (c == 0 || c == 2)) // IETF assignments + TEST-NET-1
-        || (a == 198 && b == 51)           // Documentation (198.51.100.0/24)
-        || (a == 203 && b == 0)            // Documentation (203.0.113.0/24)
-        || (a == 198 && (18..=19).contains(&b)) // Benchmarking (198.18.0.0/15)
-}
-
-/// Returns true if the IPv6 address is not globally routable.
-fn is_non_global_v6(v6: std::net::Ipv6Addr) -> bool {
-    let segs = v6.segments();
-    v6.is_loopback()                       // ::1
-        || v6.is_unspecified()             // ::
-        || v6.is_multicast()              // ff00::/8
-        || (segs[0] & 0xfe00) == 0xfc00   // Unique-local (fc00::/7)
-        || (segs[0] & 0xffc0) == 0xfe80   // Link-local (fe80::/10)
-        || (segs[0] == 0x2001 && segs[1] == 0x0db8) // Documentation (2001:db8::/32)
-        || v6.to_ipv4_mapped().is_some_and(is_non_global_v4)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::security::{AutonomyLevel, SecurityPolicy};
+    use crate::tools::url_validation::{
+        is_private_or_local_host, normalize_allowed_domains, normalize_domain,
+    };
 
     fn test_tool(allowed_domains: Vec<&str>) -> HttpRequestTool {
         test_tool_with_private(allowed_domains, false)
@@ -499,7 +673,7 @@ mod tests {
     fn validate_accepts_exact_domain() {
         let tool = test_tool(vec!["example.com"]);
         let got = tool.validate_url("https://example.com/docs").unwrap();
-        assert_eq!(got, "https://example.com/docs");
+        assert_eq!(got.as_str(), "https://example.com/docs");
     }
 
     #[test]
@@ -523,41 +697,50 @@ mod tests {
     #[test]
     fn validate_wildcard_allowlist_still_rejects_private_host() {
         let tool = test_tool(vec!["*"]);
-        let err = tool
-            .validate_url("https://localhost:8080")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("local/private"));
+        let err = tool.validate_url("https://localhost:8080").unwrap_err();
+        assert!(matches!(err, UrlValidationError::PrivateOrLocalHost { .. }));
     }
 
     #[test]
     fn validate_rejects_allowlist_miss() {
         let tool = test_tool(vec!["example.com"]);
-        let err = tool
-            .validate_url("https://google.com")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("allowed_domains"));
+        let err = tool.validate_url("https://google.com").unwrap_err();
+        assert_eq!(
+            err,
+            UrlValidationError::NotInAllowlist {
+                host: "google.com".into()
+            }
+        );
     }
 
     #[test]
     fn validate_rejects_localhost() {
         let tool = test_tool(vec!["localhost"]);
-        let err = tool
-            .validate_url("https://localhost:8080")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("local/private"));
+        let err = tool.validate_url("https://localhost:8080").unwrap_err();
+        assert!(matches!(err, UrlValidationError::PrivateOrLocalHost { .. }));
     }
 
     #[test]
     fn validate_rejects_private_ipv4() {
         let tool = test_tool(vec!["192.168.1.5"]);
-        let err = tool
-            .validate_url("https://192.168.1.5")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("local/private"));
+        let err = tool.validate_url("https://192.168.1.5").unwrap_err();
+        assert!(matches!(err, UrlValidationError::PrivateOrLocalHost { .. }));
+    }
+
+    #[test]
+    fn validate_rejects_hosts_reqwest_would_reparse_to_loopback() {
+        let tool = test_tool(vec!["allowed.com"]);
+        for url in [
+            "http://127.0.0.1\\.allowed.com/",
+            "http://127.0.0.%31/",
+            "http://0x7f.0.0.%31/",
+        ] {
+            let err = tool.validate_url(url).unwrap_err();
+            assert!(
+                matches!(err, UrlValidationError::MalformedHost { .. }),
+                "{url}: {err}"
+            );
+        }
     }
 
     #[test]
@@ -565,30 +748,36 @@ mod tests {
         let tool = test_tool(vec!["example.com"]);
         let err = tool
             .validate_url("https://example.com/hello world")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("whitespace"));
+            .unwrap_err();
+        assert!(matches!(err, UrlValidationError::ContainsWhitespace));
     }
 
     #[test]
     fn validate_rejects_userinfo() {
         let tool = test_tool(vec!["example.com"]);
-        let err = tool
-            .validate_url("https://user@example.com")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("userinfo"));
+        let err = tool.validate_url("https://user@example.com").unwrap_err();
+        assert!(matches!(err, UrlValidationError::UserinfoNotAllowed));
     }
 
     #[test]
     fn validate_requires_allowlist() {
         let security = Arc::new(SecurityPolicy::default());
         let tool = HttpRequestTool::new(security, vec![], 1_000_000, 30, false);
-        let err = tool
-            .validate_url("https://example.com")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("allowed_domains"));
+        let err = tool.validate_url("https://example.com").unwrap_err();
+        assert_eq!(err, UrlValidationError::NoAllowlistConfigured);
+    }
+
+    #[test]
+    fn url_error_messages_are_variant_specific() {
+        let miss = describe_url_error(&UrlValidationError::NotInAllowlist {
+            host: "google.com".into(),
+        });
+        assert!(miss.contains("Add this domain to [http_request].allowed_domains"));
+        let private = describe_url_error(&UrlValidationError::PrivateOrLocalHost {
+            host: "10.0.0.1".into(),
+        });
+        assert!(private.contains("allow_private_hosts"));
+        assert!(!private.contains("Add this domain"));
     }
 
     #[test]
@@ -842,49 +1031,54 @@ mod tests {
     // ── SSRF: alternate IP notation bypass defense-in-depth ─────────
     //
     // Rust's IpAddr::parse() rejects non-standard notations (octal, hex,
-    // decimal integer, zero-padded). These tests document that property
-    // so regressions are caught if the parsing strategy ever changes.
+    // decimal integer, zero-padded), but getaddrinfo on glibc resolves them.
+    // They must be decoded and blocked rather than passed through as hostnames.
+
+    #[test]
+    fn ssrf_octal_loopback_is_blocked() {
+        // 0177.0.0.1 is octal for 127.0.0.1.
+        assert!(is_private_or_local_host("0177.0.0.1"));
+    }
 
     #[test]
-    fn ssrf_octal_loopback_not_parsed_as_ip() {
-        // 0177.0.0.1 is octal for 127.0.0.1 in some languages, but
-        // Rust's IpAddr rejects it — it falls through as a hostname.
-        assert!(!is_private_or_local_host("0177.0.0.1"));
+    fn ssrf_hex_loopback_is_blocked() {
+        // 0x7f000001 is hex for 127.0.0.1.
+        assert!(is_private_or_local_host("0x7f000001"));
     }
 
     #[test]
-    fn ssrf_hex_loopback_not_parsed_as_ip() {
-        // 0x7f000001 is hex for 127.0.0.1 in some languages.
-        assert!(!is_private_or_local_host("0x7f000001"));
+    fn ssrf_decimal_loopback_is_blocked() {
+        // 2130706433 is decimal (dword) for 127.0.0.1.
+        assert!(is_private_or_local_host("2130706433"));
     }
 
     #[test]
-    fn ssrf_decimal_loopback_not_parsed_as_ip() {
-        // 2130706433 is decimal for 127.0.0.1 in some languages.
-        assert!(!is_private_or_local_host("2130706433"));
+    fn ssrf_zero_padded_loopback_is_blocked() {
+        // 127.000.000.001 uses zero-padded (octal) octets.
+        assert!(is_private_or_local_host("127.000.000.001"));
     }
 
     #[test]
-    fn ssrf_zero_padded_loopback_not_parsed_as_ip() {
-        // 127.000.000.001 uses zero-padded octets.
-        assert!(!is_private_or_local_host("127.000.000.001"));
+    fn ssrf_mixed_notation_loopback_is_blocked() {
+        assert!(is_private_or_local_host("0x7f.0.0.1"));
+        assert!(is_private_or_local_host("0x7f.1"));
+        assert!(is_private_or_local_host("0177.0x0.0.01"));
     }
 
     #[test]
     fn ssrf_alternate_notations_rejected_by_validate_url() {
-        // Even if is_private_or_local_host doesn't flag these, they
-        // fail the allowlist because they're treated as hostnames.
         let tool = test_tool(vec!["example.com"]);
         for notation in [
             "http://0177.0.0.1",
             "http://0x7f000001",
             "http://2130706433",
             "http://127.000.000.001",
+            "http://0x7f.0.0.1",
         ] {
-            let err = tool.validate_url(notation).unwrap_err().to_string();
+            let err = tool.validate_url(notation).unwrap_err();
             assert!(
-                err.contains("allowed_domains"),
-                "Expected allowlist rejection for {notation}, got: {err}"
+                matches!(err, UrlValidationError::PrivateOrLocalHost { .. }),
+                "Expected private-host rejection for {notation}, got: {err:?}"
             );
         }
     }
@@ -942,28 +1136,27 @@ mod tests {
     #[test]
     fn validate_rejects_ftp_scheme() {
         let tool = test_tool(vec!["example.com"]);
-        let err = tool
-            .validate_url("ftp://example.com")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("http://") || err.contains("https://"));
+        let err = tool.validate_url("ftp://example.com").unwrap_err();
+        assert_eq!(
+            err,
+            UrlValidationError::DisallowedScheme {
+                required: SchemeConstraint::HttpOrHttps
+            }
+        );
     }
 
     #[test]
     fn validate_rejects_empty_url() {
         let tool = test_tool(vec!["example.com"]);
-        let err = tool.validate_url("").unwrap_err().to_string();
-        assert!(err.contains("empty"));
+        let err = tool.validate_url("").unwrap_err();
+        assert!(matches!(err, UrlValidationError::EmptyUrl));
     }
 
     #[test]
     fn validate_rejects_ipv6_host() {
         let tool = test_tool(vec!["example.com"]);
-        let err = tool
-            .validate_url("http://[::1]:8080/path")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("IPv6"));
+        let err = tool.validate_url("http://[::1]:8080/path").unwrap_err();
+        assert!(matches!(err, UrlValidationError::Ipv6NotSupported));
     }
 
     // ── allow_private_hosts opt-in tests ────────────────────────
@@ -971,24 +1164,18 @@ mod tests {
     #[test]
     fn default_blocks_private_hosts() {
         let tool = test_tool(vec!["localhost", "192.168.1.5", "*"]);
-        assert!(
-            tool.validate_url("https://localhost:8080")
-                .unwrap_err()
-                .to_string()
-                .contains("local/private")
-        );
-        assert!(
-            tool.validate_url("https://192.168.1.5")
-                .unwrap_err()
-                .to_string()
-                .contains("local/private")
-        );
-        assert!(
-            tool.validate_url("https://10.0.0.1")
-                .unwrap_err()
-                .to_string()
-                .contains("local/private")
-        );
+        assert!(matches!(
+            tool.validate_url("https://localhost:8080").unwrap_err(),
+            UrlValidationError::PrivateOrLocalHost { .. }
+        ));
+        assert!(matches!(
+            tool.validate_url("https://192.168.1.5").unwrap_err(),
+            UrlValidationError::PrivateOrLocalHost { .. }
+        ));
+        assert!(matches!(
+            tool.validate_url("https://10.0.0.1").unwrap_err(),
+            UrlValidationError::PrivateOrLocalHost { .. }
+        ));
     }
 
     #[test]
@@ -1015,24 +1202,556 @@ mod tests {
     #[test]
     fn allow_private_hosts_still_requires_allowlist() {
         let tool = test_tool_with_private(vec!["example.com"], true);
-        let err = tool
-            .validate_url("https://192.168.1.5")
-            .unwrap_err()
-            .to_string();
+        let err = tool.validate_url("https://192.168.1.5").unwrap_err();
         assert!(
-            err.contains("allowed_domains"),
-            "Private host should still need allowlist match, got: {err}"
+            matches!(err, UrlValidationError::NotInAllowlist { .. }),
+            "Private host should still need allowlist match, got: {err:?}"
         );
     }
 
     #[test]
     fn allow_private_hosts_false_still_blocks() {
         let tool = test_tool_with_private(vec!["*"], false);
+        assert!(matches!(
+            tool.validate_url("https://localhost:8080").unwrap_err(),
+            UrlValidationError::PrivateOrLocalHost { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn estimate_matches_requests_sent_for_redirect_response() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(302).insert_header("location", "/elsewhere"))
+            .mount(&server)
+            .await;
+
+        let tool = test_tool_with_private(vec!["*"], true);
+        let args = json!({
+            "url": format!("http://{}/submit", server.address()),
+            "method": "POST",
+            "body": "{}"
+        });
+        let estimate = tool.estimate(&args);
+        assert_eq!(estimate.blocked, None);
+        assert_eq!(estimate.max_request_count, 1);
+        assert_eq!(estimate.worst_case_bytes, Some(1_000_001));
+
+        tool.execute(args).await.unwrap();
+        let sent = server.received_requests().await.unwrap().len();
+        assert_eq!(sent, estimate.request_count);
+    }
+
+    #[tokio::test]
+    async fn estimate_reports_the_same_refusal_as_execute() {
+        let read_only = HttpRequestTool::new(
+            Arc::new(SecurityPolicy {
+                autonomy: AutonomyLevel::ReadOnly,
+                ..SecurityPolicy::default()
+            }),
+            vec!["example.com".into()],
+            1_000_000,
+            30,
+            false,
+        );
+        let cases = [
+            (read_only, json!({"url": "https://example.com"})),
+            (
+                test_tool(vec!["example.com"]),
+                json!({"url": "https://other.org"}),
+            ),
+            (
+                test_tool(vec!["*"]),
+                json!({"url": "http://192.168.0.10/admin"}),
+            ),
+            (
+                test_tool(vec!["example.com"]),
+                json!({"url": "https://example.com", "method": "TRACE"}),
+            ),
+        ];
+
+        for (tool, args) in cases {
+            let estimate = tool.estimate(&args);
+            assert_eq!(estimate.max_request_count, 0);
+            assert_eq!(
+                estimate.may_prompt_approval,
+                args["url"] == "https://other.org"
+            );
+            let result = tool.execute(args.clone()).await.unwrap();
+            assert_eq!(estimate.blocked, result.error, "{args}");
+        }
+    }
+
+    #[tokio::test]
+    async fn cooldown_wait_counts_against_the_request_timeout() {
+        use crate::tools::url_validation::HostKey;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(1_500)))
+            .mount(&server)
+            .await;
+
+        let cooldowns = Arc::new(HostCooldowns::new());
+        let mut retry_after = reqwest::header::HeaderMap::new();
+        retry_after.insert(reqwest::header::RETRY_AFTER, "1".parse().unwrap());
+        cooldowns.record_response(
+            &HostKey::from("127.0.0.1"),
+            reqwest::StatusCode::TOO_MANY_REQUESTS,
+            &retry_after,
+        );
+        let tool = HttpRequestTool::new(
+            Arc::new(SecurityPolicy::default()),
+            vec!["*".into()],
+            1_000_000,
+            2,
+            true,
+        )
+        .with_host_cooldowns(cooldowns);
+
+        // A 1s cooldown leaves 1s of the 2s timeout for a 1.5s response.
+        let started = std::time::Instant::now();
+        let result = tool
+            .execute(json!({"url": format!("http://{}/slow", server.address())}))
+            .await
+            .unwrap();
+        assert!(!result.success, "{}", result.output);
+        assert!(started.elapsed() < Duration::from_millis(2_400));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn interceptor_header_reaches_the_wire_and_denial_sends_nothing() {
+        use crate::tools::pre_send::{InterceptDecision, OutboundRequest, PreSendInterceptor};
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        struct PartnerPolicy;
+        impl PreSendInterceptor for PartnerPolicy {
+            fn intercept(
+                &self,
+                request: &OutboundRequest<'_>,
+            ) -> anyhow::Result<InterceptDecision> {
+                Ok(if request.method == reqwest::Method::DELETE {
+                    InterceptDecision::Deny("deletes need review".into())
+                } else {
+                    InterceptDecision::Modify(vec![("X-Legal-Hold".into(), "case-7".into())])
+                })
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("x-legal-hold", "case-7"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let tool = test_tool_with_private(vec!["*"], true)
+            .with_pre_send_interceptor(Arc::new(PartnerPolicy));
+        let url = format!("http://{}/records", server.address());
+
+        let posted = tool
+            .execute(json!({"url": url, "method": "POST", "body": "{}"}))
+            .await
+            .unwrap();
+        assert!(posted.success, "{:?}", posted.error);
+
+        let deleted = tool
+            .execute(json!({"url": url, "method": "DELETE"}))
+            .await
+            .unwrap();
+        assert!(!deleted.success);
+        assert!(deleted.error.unwrap().contains("deletes need review"));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn empty_bodies_are_described_instead_of_blank() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let cases: &[(&str, u16, &[u8], &str)] = &[
+            ("/no-content", 204, b"", "(empty response, status 204)"),
+            ("/empty", 200, b"", "(empty response, status 200)"),
+            (
+                "/bom",
+                200,
+                b"\xEF\xBB\xBF",
+                "(empty response, status 200; body was 3 bytes of whitespace/BOM)",
+            ),
+            (
+                "/space",
+                200,
+                b" ",
+                "(empty response, status 200; body was 1 bytes of whitespace/BOM)",
+            ),
+            (
+                "/bom-json",
+                200,
+                b"\xEF\xBB\xBF{\"ok\":true}",
+                "{\"ok\":true}",
+            ),
+        ];
+
+        let server = MockServer::start().await;
+        for (route, status, body, _) in cases {
+            Mock::given(path(*route))
+                .respond_with(
+                    ResponseTemplate::new(*status).set_body_raw(body.to_vec(), "application/json"),
+                )
+                .mount(&server)
+                .await;
+        }
+
+        let tool = test_tool_with_private(vec!["*"], true);
+        for (route, _, _, expected) in cases {
+            let url = format!("http://{}{route}", server.address());
+            let result = tool.execute(json!({"url": url})).await.unwrap();
+            assert!(result.success, "{route}: {:?}", result.error);
+            let body = result.output.split("Response Body:\n").nth(1).unwrap();
+            assert_eq!(body, *expected, "{route}");
+        }
+    }
+
+    fn finance_tool() -> HttpRequestTool {
+        test_tool_with_private(vec!["*"], true).with_expected_content_types(HashMap::from([
+            (
+                "bank.example".to_string(),
+                vec!["application/json".to_string()],
+            ),
+            (
+                "files.bank.example".to_string(),
+                vec!["Application/*; q=1".to_string()],
+            ),
+            (
+                "127.0.0.1".to_string(),
+                vec!["application/json".to_string()],
+            ),
+        ]))
+    }
+
+    #[test]
+    fn expected_content_types_use_most_specific_domain() {
+        let tool = finance_tool();
+        let api = HostKey::from("api.bank.example");
+        let files = HostKey::from("files.bank.example");
+        let other = HostKey::from("news.example");
+        let pdf = b"%PDF-1.7".as_slice();
+
+        assert!(
+            tool.withhold_unexpected_body(&api, 200, "application/json", b"{}")
+                .is_none()
+        );
+        assert!(
+            tool.withhold_unexpected_body(&api, 200, "application/pdf", pdf)
+                .is_some()
+        );
+        assert!(
+            tool.withhold_unexpected_body(&files, 200, "application/pdf", pdf)
+                .is_none()
+        );
+        assert!(
+            tool.withhold_unexpected_body(&other, 200, "text/html", b"<html>")
+                .is_none()
+        );
+        // A JSON label on an HTML body is caught by sniffing; no label and no
+        // recognizable signature is not trusted either.
         assert!(
-            tool.validate_url("https://localhost:8080")
-                .unwrap_err()
-                .to_string()
-                .contains("local/private")
+            tool.withhold_unexpected_body(&api, 200, "application/json", b"<!DOCTYPE html>")
+                .is_some()
         );
+        assert!(
+            tool.withhold_unexpected_body(&api, 200, "", b"opaque")
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn json_only_domain_withholds_html_login_page() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let login = "<!DOCTYPE html><html><title>Sign in</title><form>secret-form</form></html>";
+        let server = MockServer::start().await;
+        Mock::given(path("/balances"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw(login, "text/html; charset=utf-8"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(path("/mislabeled"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(login, "application/json"))
+            .mount(&server)
+            .await;
+        Mock::given(path("/ok"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_raw("{\"balance\":1}", "application/json"),
+            )
+            .mount(&server)
+            .await;
+
+        let tool = finance_tool();
+        for route in ["/balances", "/mislabeled"] {
+            let url = format!("http://{}{route}", server.address());
+            let result = tool.execute(json!({"url": url})).await.unwrap();
+            assert!(!result.success, "{route}");
+            assert!(!result.output.contains("secret-form"), "{route}");
+            let outcome: serde_json::Value = serde_json::from_str(&result.output).unwrap();
+            assert_eq!(outcome["outcome"], "unexpected_content_type");
+            assert_eq!(outcome["status"], 200);
+            assert_eq!(outcome["sniffed_content_type"], "text/html");
+            assert_eq!(
+                outcome["expected_content_types"],
+                json!(["application/json"])
+            );
+            assert!(outcome["hint"].as_str().unwrap().contains("login"));
+            assert!(result.error.unwrap().contains("withheld"), "{route}");
+        }
+
+        let url = format!("http://{}/ok", server.address());
+        let result = tool.execute(json!({"url": url})).await.unwrap();
+        assert!(result.success);
+        assert!(result.output.ends_with("{\"balance\":1}"));
+    }
+
+    #[tokio::test]
+    async fn permissive_domain_still_receives_html() {
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(wiremock::matchers::any())
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<html>hi</html>", "text/html"))
+            .mount(&server)
+            .await;
+
+        let tool =
+            test_tool_with_private(vec!["*"], true).with_expected_content_types(HashMap::from([(
+                "bank.example".to_string(),
+                vec!["application/json".to_string()],
+            )]));
+        let url = format!("http://{}/page", server.address());
+        let result = tool.execute(json!({"url": url})).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.ends_with("<html>hi</html>"));
+    }
+
+    // ── Header order and casing on the wire ─────────────────────
+
+    /// Accept one connection, answer `200 ok`, and return the request head
+    /// exactly as it arrived.
+    fn capture_request_head(listener: tokio::net::TcpListener) -> tokio::task::JoinHandle<String> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut received = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !received.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                assert!(n > 0, "connection closed before end of headers");
+                received.extend_from_slice(&buf[..n]);
+            }
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok")
+                .await
+                .unwrap();
+            let head_len = received.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+            String::from_utf8(received[..head_len].to_vec()).unwrap()
+        })
+    }
+
+    #[tokio::test]
+    async fn headers_are_written_in_canonical_order_with_configured_casing() {
+        let cases: [(Vec<String>, &[&str]); 2] = [
+            (
+                vec![],
+                &[
+                    "host",
+                    "accept",
+                    "content-type",
+                    "authorization",
+                    "b-custom",
+                    "x-trace",
+                    "content-length",
+                ],
+            ),
+            (
+                vec!["127.0.0.1".into()],
+                &[
+                    "Host",
+                    "Accept",
+                    "Content-Type",
+                    "Authorization",
+                    "B-Custom",
+                    "X-Trace",
+                    "Content-Length",
+                ],
+            ),
+        ];
+
+        for (casing_domains, expected_names) in cases {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let head = capture_request_head(listener);
+
+            let tool =
+                test_tool_with_private(vec!["*"], true).with_browser_like_casing(casing_domains);
+            let result = tool
+                .execute(json!({
+                    "url": format!("http://{addr}/submit"),
+                    "method": "POST",
+                    "headers": {
+                        "X-Trace": "t",
+                        "Authorization": "Bearer k",
+                        "b-custom": "1",
+                        "Content-Type": "application/json"
+                    },
+                    "body": "{}"
+                }))
+                .await
+                .unwrap();
+            assert!(result.success, "{:?}", result.error);
+
+            let head = head.await.unwrap();
+            let mut lines = head.split("\r\n");
+            assert_eq!(lines.next(), Some("POST /submit HTTP/1.1"));
+            let names: Vec<&str> = lines.map(|line| line.split_once(": ").unwrap().0).collect();
+            assert_eq!(names, expected_names, "{head}");
+            assert!(head.contains(&format!(": 127.0.0.1:{}\r\n", addr.port())));
+        }
+    }
+
+    // ── Truncated transfers ─────────────────────────────────────
+
+    /// Accept one connection, read the request, send `response` and close.
+    fn serve_once(
+        listener: tokio::net::TcpListener,
+        response: &'static [u8],
+    ) -> tokio::task::JoinHandle<()> {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut received = Vec::new();
+            let mut buf = [0u8; 1024];
+            let head_end = loop {
+                if let Some(pos) = received.windows(4).position(|w| w == b"\r\n\r\n") {
+                    break pos + 4;
+                }
+                let n = socket.read(&mut buf).await.unwrap();
+                assert!(n > 0, "connection closed before end of headers");
+                received.extend_from_slice(&buf[..n]);
+            };
+            let head = String::from_utf8_lossy(&received[..head_end]).to_ascii_lowercase();
+            let body_len: usize = head
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length: "))
+                .map_or(0, |len| len.trim().parse().unwrap());
+            while received.len() < head_end + body_len {
+                let n = socket.read(&mut buf).await.unwrap();
+                received.extend_from_slice(&buf[..n]);
+            }
+            socket.write_all(response).await.unwrap();
+            socket.shutdown().await.unwrap();
+        })
+    }
+
+    #[tokio::test]
+    async fn early_close_is_reported_as_truncated_transfer() {
+        const SHORT_CONTENT_LENGTH: &[u8] =
+            b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+            content-length: 100\r\n\r\n{\"items\":[1,2";
+        const UNTERMINATED_CHUNKED: &[u8] =
+            b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+            transfer-encoding: chunked\r\n\r\nd\r\n{\"items\":[1,2\r\n";
+
+        let cases = [
+            (
+                "GET",
+                SHORT_CONTENT_LENGTH,
+                "received 13 of 100 bytes declared by Content-Length",
+                "Wait a while, then retry.",
+            ),
+            (
+                "GET",
+                UNTERMINATED_CHUNKED,
+                "chunked body ended after 13 bytes",
+                "Wait a while, then retry.",
+            ),
+            (
+                "POST",
+                SHORT_CONTENT_LENGTH,
+                "received 13 of 100 bytes",
+                "Do not retry this request.",
+            ),
+        ];
+
+        for (method, response, detail, guidance) in cases {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let server = serve_once(listener, response);
+
+            let tool = test_tool_with_private(vec!["*"], true);
+            let result = tool
+                .execute(json!({
+                    "url": format!("http://{addr}/items"),
+                    "method": method,
+                    "body": "{}"
+                }))
+                .await
+                .unwrap();
+            server.await.unwrap();
+
+            assert!(!result.success, "{method} {detail}");
+            let err = result.error.unwrap();
+            assert!(err.contains(detail), "{err}");
+            assert!(err.contains("not valid JSON because of this"), "{err}");
+            assert!(err.ends_with(guidance), "{err}");
+            assert!(
+                !result.output.contains("{\"items\":[1,2"),
+                "{}",
+                result.output
+            );
+            assert!(
+                result.output.contains("truncated_transfer: true"),
+                "{}",
+                result.output
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn truncated_body_of_unexpected_type_is_withheld() {
+        const TRUNCATED_LOGIN_PAGE: &[u8] = b"HTTP/1.1 200 OK\r\ncontent-type: text/html\r\n\
+            content-length: 100\r\n\r\n<html><form>session=SECRET";
+        const TRUNCATED_JSON: &[u8] = b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\n\
+            content-length: 100\r\n\r\n{\"items\":[1,2";
+
+        let cases = [
+            (TRUNCATED_LOGIN_PAGE, "unexpected_content_type"),
+            (TRUNCATED_JSON, "truncated_transfer: true"),
+        ];
+        for (response, expected) in cases {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let server = serve_once(listener, response);
+
+            let result = finance_tool()
+                .execute(json!({"url": format!("http://{addr}/items")}))
+                .await
+                .unwrap();
+            server.await.unwrap();
+
+            assert!(!result.success);
+            assert!(result.output.contains(expected), "{}", result.output);
+            assert!(!result.output.contains("SECRET"), "{}", result.output);
+        }
     }
 }
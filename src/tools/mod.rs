@@ -53,6 +53,9 @@ pub mod hardware_board_info;
 pub mod hardware_memory_map;
 #[cfg(feature = "hardware")]
 pub mod hardware_memory_read;
+pub mod header_order;
+pub mod host_cooldown;
+pub mod html_converter;
 pub mod http_request;
 pub mod image_gen;
 pub mod image_info;
@@ -81,13 +84,17 @@ pub mod opencode_cli;
 pub mod pdf_read;
 pub mod pipeline;
 pub mod poll;
+pub mod pre_send;
 pub mod project_intel;
+pub mod project_origins;
 pub mod proxy_config;
 pub mod pushover;
 pub mod reaction;
 pub mod read_skill;
 pub mod report_template_tool;
 pub mod report_templates;
+pub mod response_body;
+pub mod retry_guidance;
 pub mod schedule;
 pub mod schema;
 pub mod screenshot;
@@ -105,6 +112,7 @@ pub mod swarm;
 pub mod text_browser;
 pub mod tool_search;
 pub mod traits;
+pub mod url_validation;
 pub mod verifiable_intent;
 pub mod weather_tool;
 pub mod web_fetch;
@@ -204,7 +212,7 @@ pub use text_browser::TextBrowserTool;
 pub use tool_search::ToolSearchTool;
 pub use traits::Tool;
 #[allow(unused_imports)]
-pub use traits::{ToolResult, ToolSpec};
+pub use traits::{CostEstimate, ShortCircuit, ToolResult, ToolSpec};
 pub use verifiable_intent::VerifiableIntentTool;
 pub use weather_tool::WeatherTool;
 pub use web_fetch::WebFetchTool;
@@ -544,26 +552,85 @@ pub fn all_tools_with_runtime(
         }
     }
 
+    let project_origins =
+        if http_config.trust_project_origins || web_fetch_config.trust_project_origins {
+            project_origins::scan_project_origins(workspace_dir)
+        } else {
+            Vec::new()
+        };
+    // Origins added on the project's say-so go into the audit trail.
+    let origins_audit = if project_origins.is_empty() || !root_config.security.audit.enabled {
+        None
+    } else {
+        let zeroclaw_dir = root_config
+            .config_path
+            .parent()
+            .map(std::path::PathBuf::from)
+            .unwrap_or_default();
+        match crate::security::AuditLogger::new(root_config.security.audit.clone(), zeroclaw_dir) {
+            Ok(audit) => Some(audit),
+            Err(e) => {
+                tracing::warn!("project origins: audit log unavailable: {e}");
+                None
+            }
+        }
+    };
+    let allowlist_for = |allowed: &[String], trusted: bool, tool: &str| {
+        if trusted {
+            project_origins::extend_allowlist(
+                allowed,
+                &project_origins,
+                tool,
+                origins_audit.as_ref(),
+            )
+        } else {
+            allowed.to_vec()
+        }
+    };
+
+    // One cooldown table so a 429 seen by either tool holds back both.
+    let host_cooldowns = Arc::new(host_cooldown::HostCooldowns::new());
+
     if http_config.enabled {
-        tool_arcs.push(Arc::new(HttpRequestTool::new(
-            security.clone(),
-            http_config.allowed_domains.clone(),
-            http_config.max_response_size,
-            http_config.timeout_secs,
-            http_config.allow_private_hosts,
-        )));
+        tool_arcs.push(Arc::new(
+            HttpRequestTool::new(
+                security.clone(),
+                allowlist_for(
+                    &http_config.allowed_domains,
+                    http_config.trust_project_origins,
+                    "http_request",
+                ),
+                http_config.max_response_size,
+                http_config.timeout_secs,
+                http_config.allow_private_hosts,
+            )
+            .with_expected_content_types(http_config.expected_content_types.clone())
+            .with_host_cooldowns(host_cooldowns.clone())
+            .with_browser_like_casing(http_config.browser_like_casing_domains.clone())
+            .with_log_fragments(root_config.security.audit.log_fragments),
+        ));
     }
 
     if web_fetch_config.enabled {
-        tool_arcs.push(Arc::new(WebFetchTool::new(
-            security.clone(),
-            web_fetch_config.allowed_domains.clone(),
-            web_fetch_config.blocked_domains.clone(),
-            web_fetch_config.max_response_size,
-            web_fetch_config.timeout_secs,
-            web_fetch_config.firecrawl.clone(),
-            web_fetch_config.allowed_private_hosts.clone(),
-        )));
+        tool_arcs.push(Arc::new(
+            WebFetchTool::new(
+                security.clone(),
+                allowlist_for(
+                    &web_fetch_config.allowed_domains,
+                    web_fetch_config.trust_project_origins,
+                    "web_fetch",
+                ),
+                web_fetch_config.blocked_domains.clone(),
+                web_fetch_config.max_response_size,
+                web_fetch_config.timeout_secs,
+                web_fetch_config.firecrawl.clone(),
+                web_fetch_config.allowed_private_hosts.clone(),
+            )
+            .with_max_redirects(web_fetch_config.max_redirects)
+            .with_host_cooldowns(host_cooldowns.clone())
+            .with_browser_like_casing(web_fetch_config.browser_like_casing_domains.clone())
+            .with_log_fragments(root_config.security.audit.log_fragments),
+        ));
     }
 
     // Text browser tool (headless text-based browser rendering)
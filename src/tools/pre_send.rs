@@ -0,0 +1,391 @@
+//! Last-moment inspection of outbound requests by embedders.
+//!
+//! URL policy decides *where* a tool may connect; a [`PreSendInterceptor`]
+//! sees the complete request (method, URL, headers, body hash) immediately
+//! before it goes on the wire and may let it through, veto it, or add headers.
+//! Install one with `with_pre_send_interceptor` on `web_fetch` or
+//! `http_request`.
+//!
+//! For every network attempt, including each redirect hop, the order is:
+//!
+//! 1. defaults and tool-supplied headers, in the canonical order of
+//!    [`header_order`](super::header_order)
+//! 2. transforms
+//! 3. the interceptor, invoked exactly once
+//! 4. signing
+//!
+//! No tool has transforms or signing yet; the order fixes where they go.
+//!
+//! Interceptors may only add headers, never change the URL or body, so a
+//! signature computed afterwards still covers what the interceptor approved.
+//! Time spent in the interceptor counts against the request timeout, and any
+//! interceptor failure blocks the request.
+
+use super::header_order::sort_headers;
+use super::url_validation::url_for_log;
+use reqwest::header::{CONTENT_LENGTH, HOST, HeaderName, TRANSFER_ENCODING};
+use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
+
+/// Read-only view of a request that is about to be sent.
+#[derive(Debug)]
+pub struct OutboundRequest<'a> {
+    /// Name of the tool issuing the request.
+    pub tool: &'a str,
+    pub method: &'a reqwest::Method,
+    pub url: &'a reqwest::Url,
+    /// Headers after defaults, exactly as they will be sent.
+    pub headers: &'a reqwest::header::HeaderMap,
+    /// Hex SHA-256 of the body, or `None` when the request has no body.
+    pub body_sha256: Option<String>,
+}
+
+/// What to do with an intercepted request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterceptDecision {
+    /// Send the request unchanged.
+    Allow,
+    /// Do not send the request; the reason is reported to the caller.
+    Deny(String),
+    /// Send the request with these headers added. Headers already present
+    /// on the request cannot be replaced.
+    Modify(Vec<(String, String)>),
+}
+
+/// A request vetoed by [`InterceptDecision::Deny`].
+///
+/// Callers match on this type to avoid retrying the same target by another
+/// route, such as a fetch fallback service.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Request to {url} denied by pre-send interceptor: {reason}")]
+pub struct InterceptDenied {
+    pub url: String,
+    pub reason: String,
+}
+
+/// Whether `name` is derived by the HTTP stack from the URL and body. An
+/// interceptor setting one could retarget the request or desync its framing.
+fn is_client_managed(name: &HeaderName) -> bool {
+    *name == HOST || *name == CONTENT_LENGTH || *name == TRANSFER_ENCODING
+}
+
+/// Embedder hook invoked once per network attempt, just before send.
+pub trait PreSendInterceptor: Send + Sync {
+    fn intercept(&self, request: &OutboundRequest<'_>) -> anyhow::Result<InterceptDecision>;
+}
+
+/// Run `interceptor` against `request` and apply its decision in place.
+///
+/// Fails closed: a denial (as [`InterceptDenied`]), an interceptor error, an
+/// invalid, conflicting or protected header, or an interceptor that used up
+/// the whole `timeout` all return an error, and the caller must not send the
+/// request. On success the
+/// request's timeout is set to whatever remains of `timeout`.
+///
+/// Errors name the URL as logs do: its fragment is redacted unless
+/// `log_fragments` is set.
+pub fn intercept_request(
+    interceptor: &dyn PreSendInterceptor,
+    tool: &str,
+    request: &mut reqwest::Request,
+    timeout: Duration,
+    log_fragments: bool,
+) -> anyhow::Result<()> {
+    let started = Instant::now();
+    let body_sha256 = request
+        .body()
+        .and_then(reqwest::Body::as_bytes)
+        .map(|bytes| hex::encode(Sha256::digest(bytes)));
+    let view = OutboundRequest {
+        tool,
+        method: request.method(),
+        url: request.url(),
+        headers: request.headers(),
+        body_sha256,
+    };
+    let url = url_for_log(request.url().as_str(), log_fragments).into_owned();
+
+    let decision = interceptor
+        .intercept(&view)
+        .map_err(|e| anyhow::anyhow!("Pre-send interceptor failed for {url}: {e}"))?;
+
+    let remaining = timeout
+        .checked_sub(started.elapsed())
+        .filter(|left| !left.is_zero())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Pre-send interceptor for {url} used up the {}s request timeout",
+                timeout.as_secs_f64()
+            )
+        })?;
+
+    match decision {
+        InterceptDecision::Allow => {}
+        InterceptDecision::Deny(reason) => {
+            return Err(InterceptDenied { url, reason }.into());
+        }
+        InterceptDecision::Modify(additions) => {
+            for (name, value) in additions {
+                let header_name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| {
+                    anyhow::anyhow!("Pre-send interceptor added invalid header name '{name}': {e}")
+                })?;
+                let header_value = reqwest::header::HeaderValue::from_str(&value).map_err(|e| {
+                    anyhow::anyhow!("Pre-send interceptor added invalid value for '{name}': {e}")
+                })?;
+                if is_client_managed(&header_name) {
+                    anyhow::bail!(
+                        "Pre-send interceptor may only add headers, but '{name}' is set by the HTTP client"
+                    );
+                }
+                if request.headers().contains_key(&header_name) {
+                    anyhow::bail!(
+                        "Pre-send interceptor may only add headers, but '{name}' is already set"
+                    );
+                }
+                request.headers_mut().insert(header_name, header_value);
+            }
+            sort_headers(request.headers_mut());
+        }
+    }
+
+    *request.timeout_mut() = Some(remaining);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    struct Fixed(InterceptDecision);
+
+    impl PreSendInterceptor for Fixed {
+        fn intercept(&self, _request: &OutboundRequest<'_>) -> anyhow::Result<InterceptDecision> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn request(body: Option<&str>) -> reqwest::Request {
+        let client = reqwest::Client::new();
+        let mut builder = client
+            .post("https://partner.example.com/api")
+            .header("authorization", "Bearer t");
+        if let Some(body) = body {
+            builder = builder.body(body.to_string());
+        }
+        builder.build().unwrap()
+    }
+
+    const TIMEOUT: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn allow_leaves_request_unchanged_and_sets_remaining_timeout() {
+        let mut req = request(None);
+        intercept_request(
+            &Fixed(InterceptDecision::Allow),
+            "t",
+            &mut req,
+            TIMEOUT,
+            false,
+        )
+        .unwrap();
+        assert_eq!(req.headers().len(), 1);
+        let left = req.timeout().copied().unwrap();
+        assert!(left <= TIMEOUT && left > Duration::from_secs(29));
+    }
+
+    #[test]
+    fn modify_adds_headers() {
+        let mut req = request(None);
+        let decision = InterceptDecision::Modify(vec![("X-Legal-Hold".into(), "case-7".into())]);
+        intercept_request(&Fixed(decision), "t", &mut req, TIMEOUT, false).unwrap();
+        assert_eq!(req.headers()["x-legal-hold"], "case-7");
+        assert_eq!(req.headers()["authorization"], "Bearer t");
+    }
+
+    #[test]
+    fn failures_fail_closed() {
+        struct Broken;
+        impl PreSendInterceptor for Broken {
+            fn intercept(&self, _: &OutboundRequest<'_>) -> anyhow::Result<InterceptDecision> {
+                anyhow::bail!("policy store unreachable")
+            }
+        }
+        struct Slow;
+        impl PreSendInterceptor for Slow {
+            fn intercept(&self, _: &OutboundRequest<'_>) -> anyhow::Result<InterceptDecision> {
+                std::thread::sleep(Duration::from_millis(30));
+                Ok(InterceptDecision::Allow)
+            }
+        }
+
+        let cases: Vec<(Box<dyn PreSendInterceptor>, Duration, &str)> = vec![
+            (
+                Box::new(Fixed(InterceptDecision::Deny("no partner contract".into()))),
+                TIMEOUT,
+                "denied by pre-send interceptor: no partner contract",
+            ),
+            (Box::new(Broken), TIMEOUT, "policy store unreachable"),
+            (
+                Box::new(Fixed(InterceptDecision::Modify(vec![(
+                    "Authorization".into(),
+                    "Bearer other".into(),
+                )]))),
+                TIMEOUT,
+                "may only add headers",
+            ),
+            (
+                Box::new(Fixed(InterceptDecision::Modify(vec![(
+                    "Host".into(),
+                    "internal.example".into(),
+                )]))),
+                TIMEOUT,
+                "'Host' is set by the HTTP client",
+            ),
+            (
+                Box::new(Fixed(InterceptDecision::Modify(vec![(
+                    "Content-Length".into(),
+                    "0".into(),
+                )]))),
+                TIMEOUT,
+                "'Content-Length' is set by the HTTP client",
+            ),
+            (
+                Box::new(Fixed(InterceptDecision::Modify(vec![(
+                    "transfer-encoding".into(),
+                    "chunked".into(),
+                )]))),
+                TIMEOUT,
+                "'transfer-encoding' is set by the HTTP client",
+            ),
+            (
+                Box::new(Fixed(InterceptDecision::Modify(vec![(
+                    "bad header".into(),
+                    "v".into(),
+                )]))),
+                TIMEOUT,
+                "invalid header name",
+            ),
+            (
+                Box::new(Slow),
+                Duration::from_millis(10),
+                "used up the 0.01s request timeout",
+            ),
+        ];
+
+        for (interceptor, timeout, needle) in cases {
+            let mut req = request(None);
+            let err = intercept_request(interceptor.as_ref(), "t", &mut req, timeout, false)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(needle), "{err}");
+            assert_eq!(req.headers()["authorization"], "Bearer t");
+            assert!(!req.headers().contains_key(reqwest::header::HOST));
+            assert!(!req.headers().contains_key(reqwest::header::CONTENT_LENGTH));
+        }
+    }
+
+    #[test]
+    fn denial_is_typed() {
+        let mut req = request(None);
+        let err = intercept_request(
+            &Fixed(InterceptDecision::Deny("no partner contract".into())),
+            "t",
+            &mut req,
+            TIMEOUT,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InterceptDenied>(),
+            Some(&InterceptDenied {
+                url: "https://partner.example.com/api".into(),
+                reason: "no partner contract".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn errors_redact_url_fragments_unless_logging_them() {
+        struct Broken;
+        impl PreSendInterceptor for Broken {
+            fn intercept(&self, _: &OutboundRequest<'_>) -> anyhow::Result<InterceptDecision> {
+                anyhow::bail!("policy store unreachable")
+            }
+        }
+        let deny = Fixed(InterceptDecision::Deny("no partner contract".into()));
+        let with_fragment = || {
+            let mut req = request(None);
+            req.url_mut().set_fragment(Some("access_token=secret"));
+            req
+        };
+
+        for interceptor in [&deny as &dyn PreSendInterceptor, &Broken] {
+            let err = intercept_request(interceptor, "t", &mut with_fragment(), TIMEOUT, false)
+                .unwrap_err()
+                .to_string();
+            assert!(
+                err.contains("https://partner.example.com/api#[redacted]"),
+                "{err}"
+            );
+            assert!(!err.contains("secret"), "{err}");
+
+            let err = intercept_request(interceptor, "t", &mut with_fragment(), TIMEOUT, true)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("#access_token=secret"), "{err}");
+        }
+
+        let err = intercept_request(&deny, "t", &mut with_fragment(), TIMEOUT, false).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InterceptDenied>().unwrap().url,
+            "https://partner.example.com/api#[redacted]"
+        );
+    }
+
+    #[test]
+    fn interceptor_sees_final_request_and_body_hash() {
+        #[derive(Default)]
+        struct Recording(Mutex<Vec<(String, String, Option<String>)>>);
+        impl PreSendInterceptor for Recording {
+            fn intercept(&self, req: &OutboundRequest<'_>) -> anyhow::Result<InterceptDecision> {
+                self.0.lock().push((
+                    format!("{} {} {}", req.tool, req.method, req.url),
+                    req.headers["authorization"].to_str()?.to_string(),
+                    req.body_sha256.clone(),
+                ));
+                Ok(InterceptDecision::Allow)
+            }
+        }
+
+        let recording = Recording::default();
+        intercept_request(
+            &recording,
+            "http_request",
+            &mut request(Some("abc")),
+            TIMEOUT,
+            false,
+        )
+        .unwrap();
+        intercept_request(
+            &recording,
+            "http_request",
+            &mut request(None),
+            TIMEOUT,
+            false,
+        )
+        .unwrap();
+
+        let seen = recording.0.lock();
+        assert_eq!(
+            seen[0].0,
+            "http_request POST https://partner.example.com/api"
+        );
+        assert_eq!(seen[0].1, "Bearer t");
+        assert_eq!(
+            seen[0].2.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(seen[1].2, None);
+    }
+}
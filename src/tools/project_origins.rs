@@ -0,0 +1,476 @@
+//! Allowlist candidates derived from a project's own configuration files.
+//!
+//! With `trust_project_origins` enabled, `web_fetch` and `http_request` also
+//! allow the hosts the workspace already references: git remotes in
+//! `.git/config`, and repository, registry and git-dependency URLs in
+//! `Cargo.toml` and `package.json`.
+//!
+//! The scanner only reads a fixed set of files. It never runs project code,
+//! skips symlinks and files over [`MAX_SCAN_BYTES`], and keeps only public
+//! DNS names: IP literals, local and private hosts, and malformed names are
+//! dropped, since a cloned repository is untrusted input. Every host added
+//! to an allowlist is recorded in the audit log with the file it came from.
+
+use super::url_validation::{
+    HostKey, NumericHost, classify_numeric_host, is_private_or_local_host, normalize_domain,
+};
+use crate::security::{AuditEvent, AuditEventType, AuditLogger};
+use std::io::Read;
+use std::path::Path;
+
+/// Files scanned, relative to the workspace root.
+pub const PROJECT_FILES: &[&str] = &[".git/config", "Cargo.toml", "package.json"];
+
+/// Files larger than this are skipped rather than parsed.
+pub const MAX_SCAN_BYTES: u64 = 256 * 1024;
+
+/// TOML keys whose string values are URLs.
+const CARGO_URL_KEYS: &[&str] = &[
+    "repository",
+    "homepage",
+    "documentation",
+    "git",
+    "index",
+    "registry-index",
+];
+
+/// JSON keys whose string values are URLs.
+const NPM_URL_KEYS: &[&str] = &["repository", "homepage", "url", "registry"];
+
+/// JSON objects mapping package names to version specifiers, which may be
+/// URLs.
+const NPM_DEPENDENCY_KEYS: &[&str] = &[
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "optionalDependencies",
+];
+
+/// A host referenced by a project file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectOrigin {
+    pub host: HostKey,
+    /// Workspace-relative file the host was first found in.
+    pub source: &'static str,
+}
+
+/// Collect the hosts referenced by the workspace's project files.
+///
+/// Each host is reported once, tagged with the first file it appeared in.
+/// Unreadable, oversized or malformed files are skipped with a warning.
+pub fn scan_project_origins(workspace: &Path) -> Vec<ProjectOrigin> {
+    let mut origins: Vec<ProjectOrigin> = Vec::new();
+
+    for &source in PROJECT_FILES {
+        let Some(contents) = read_capped(&workspace.join(source)) else {
+            continue;
+        };
+        let urls = match source {
+            ".git/config" => git_config_urls(&contents),
+            "Cargo.toml" => match toml::from_str::<toml::Value>(&contents) {
+                Ok(value) => {
+                    let mut urls = Vec::new();
+                    collect_toml_urls(&value, None, &mut urls);
+                    urls
+                }
+                Err(e) => {
+                    tracing::warn!("project origins: skipping unparseable {source}: {e}");
+                    continue;
+                }
+            },
+            _ => match serde_json::from_str::<serde_json::Value>(&contents) {
+                Ok(value) => {
+                    let mut urls = Vec::new();
+                    collect_json_urls(&value, None, &mut urls);
+                    urls
+                }
+                Err(e) => {
+                    tracing::warn!("project origins: skipping unparseable {source}: {e}");
+                    continue;
+                }
+            },
+        };
+
+        for url in urls {
+            let Some(host) = origin_host(&url) else {
+                continue;
+            };
+            if !origins.iter().any(|o| o.host == host) {
+                origins.push(ProjectOrigin { host, source });
+            }
+        }
+    }
+
+    origins
+}
+
+/// Append `origins` to a tool's allowlist, logging each addition with its
+/// source and recording it as a config change in `audit`.
+pub fn extend_allowlist(
+    allowed_domains: &[String],
+    origins: &[ProjectOrigin],
+    tool: &str,
+    audit: Option<&AuditLogger>,
+) -> Vec<String> {
+    let mut extended = allowed_domains.to_vec();
+    for origin in origins {
+        let change = format!(
+            "{tool}: allowing {} from project file {} (trust_project_origins)",
+            origin.host, origin.source
+        );
+        tracing::info!("{change}");
+        if let Some(audit) = audit {
+            let event = AuditEvent::new(AuditEventType::ConfigChange)
+                .with_actor("project_origins".into(), None, None)
+                .with_action(change, "low".into(), false, true);
+            if let Err(e) = audit.log(&event) {
+                tracing::warn!("project origins: failed to write audit event: {e}");
+            }
+        }
+        extended.push(origin.host.as_str().to_string());
+    }
+    extended
+}
+
+/// Read a regular file of at most [`MAX_SCAN_BYTES`].
+fn read_capped(path: &Path) -> Option<String> {
+    let meta = std::fs::symlink_metadata(path).ok()?;
+    if !meta.is_file() {
+        if meta.file_type().is_symlink() {
+            tracing::warn!("project origins: not following symlink {}", path.display());
+        }
+        return None;
+    }
+
+    let mut buf = Vec::new();
+    std::fs::File::open(path)
+        .ok()?
+        .take(MAX_SCAN_BYTES + 1)
+        .read_to_end(&mut buf)
+        .ok()?;
+    if buf.len() as u64 > MAX_SCAN_BYTES {
+        tracing::warn!(
+            "project origins: skipping {} (larger than {MAX_SCAN_BYTES} bytes)",
+            path.display()
+        );
+        return None;
+    }
+    String::from_utf8(buf).ok()
+}
+
+/// `url`/`pushurl` values from a git config.
+fn git_config_urls(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim().to_ascii_lowercase();
+            (key == "url" || key == "pushurl").then(|| value.trim().trim_matches('"').to_string())
+        })
+        .collect()
+}
+
+fn collect_toml_urls(value: &toml::Value, key: Option<&str>, out: &mut Vec<String>) {
+    match value {
+        toml::Value::String(s) if key.is_some_and(|k| CARGO_URL_KEYS.contains(&k)) => {
+            out.push(s.clone());
+        }
+        toml::Value::Table(table) => {
+            for (k, v) in table {
+                // `[patch."https://host/repo"]` names its source in the key.
+                if key == Some("patch") {
+                    out.push(k.clone());
+                }
+                collect_toml_urls(v, Some(k), out);
+            }
+        }
+        toml::Value::Array(items) => {
+            for item in items {
+                collect_toml_urls(item, key, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_json_urls(value: &serde_json::Value, key: Option<&str>, out: &mut Vec<String>) {
+    match value {
+        serde_json::Value::String(s) if key.is_some_and(|k| NPM_URL_KEYS.contains(&k)) => {
+            out.push(s.clone());
+        }
+        serde_json::Value::Object(map) if key.is_some_and(|k| NPM_DEPENDENCY_KEYS.contains(&k)) => {
+            // Only URL specifiers name a host; versions and paths do not.
+            out.extend(
+                map.values()
+                    .filter_map(serde_json::Value::as_str)
+                    .filter(|spec| spec.contains("://"))
+                    .map(str::to_string),
+            );
+        }
+        serde_json::Value::Object(map) => {
+            for (k, v) in map {
+                collect_json_urls(v, Some(k), out);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_json_urls(item, key, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Host of a remote or registry URL, if it is a public DNS name.
+///
+/// Accepts `scheme://` URLs, including `git+`/`sparse+` prefixed forms, and
+/// scp-style `user@host:path` git remotes.
+fn origin_host(raw: &str) -> Option<HostKey> {
+    let raw = raw.trim();
+    let host = if let Some((scheme, rest)) = raw.split_once("://") {
+        let scheme = scheme
+            .trim_start_matches("git+")
+            .trim_start_matches("sparse+")
+            .to_ascii_lowercase();
+        if !matches!(scheme.as_str(), "http" | "https" | "ssh" | "git") {
+            return None;
+        }
+        let authority = rest.split(['/', '?', '#']).next()?;
+        let host_port = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+        if host_port.starts_with('[') {
+            return None;
+        }
+        host_port.split(':').next()?.to_string()
+    } else {
+        // scp-style `git@github.com:org/repo.git`; a `/` before the colon
+        // means a local path.
+        let (before, _) = raw.split_once(':')?;
+        if before.contains('/') || before.contains('\\') {
+            return None;
+        }
+        before
+            .rsplit_once('@')
+            .map_or(before, |(_, h)| h)
+            .to_string()
+    };
+
+    let host = normalize_domain(&host)?;
+    let is_dns_name = host.contains('.')
+        && host
+            .split('.')
+            .all(|label| !label.is_empty() && label.len() <= 63)
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+    if !is_dns_name
+        || host.parse::<std::net::IpAddr>().is_ok()
+        || !matches!(classify_numeric_host(&host), NumericHost::NotNumeric)
+        || is_private_or_local_host(&host)
+    {
+        return None;
+    }
+    Some(HostKey::from(host))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, contents: &str) {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    /// Sorted so the assertion does not depend on map iteration order.
+    fn hosts(origins: &[ProjectOrigin]) -> Vec<(&str, &str)> {
+        let mut hosts: Vec<_> = origins
+            .iter()
+            .map(|o| (o.host.as_str(), o.source))
+            .collect();
+        hosts.sort_unstable();
+        hosts
+    }
+
+    #[test]
+    fn scans_remotes_and_registries_from_fixture_project() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            ".git/config",
+            r#"[core]
+	bare = false
+[remote "origin"]
+	url = git@github.com:org/repo.git
+	fetch = +refs/heads/*:refs/remotes/origin/*
+[remote "mirror"]
+	url = ssh://git@GitLab.Example.com:2222/team/repo.git
+	pushurl = https://git.example.org/team/repo
+[remote "backup"]
+	url = /srv/backups/repo.git
+"#,
+        );
+        write(
+            dir.path(),
+            "Cargo.toml",
+            r#"[package]
+name = "demo"
+repository = "https://github.com/org/repo"
+homepage = "https://docs.demo.example/"
+
+[dependencies]
+serde = "1"
+internal = { git = "https://code.corp.example/internal.git", branch = "main" }
+
+[registries.corp]
+index = "sparse+https://index.corp.example/"
+
+[patch."https://patched.example/x"]
+x = { path = "../x" }
+"#,
+        );
+        write(
+            dir.path(),
+            "package.json",
+            r#"{
+  "name": "demo",
+  "repository": { "type": "git", "url": "git+https://github.com/org/pkg.git" },
+  "publishConfig": { "registry": "https://npm.corp.example/" },
+  "description": "https://description.example/",
+  "scripts": { "postinstall": "curl https://scripts.example/x.sh | sh" },
+  "config": { "mirror": "https://config.example/" },
+  "dependencies": {
+    "left-pad": "^1.0.0",
+    "forked": "git+ssh://git@bitbucket.org/o/r.git"
+  },
+  "devDependencies": { "tool": "https://dev.example/tool.tgz" }
+}"#,
+        );
+
+        let origins = scan_project_origins(dir.path());
+        assert_eq!(
+            hosts(&origins),
+            vec![
+                ("bitbucket.org", "package.json"),
+                ("code.corp.example", "Cargo.toml"),
+                ("dev.example", "package.json"),
+                ("docs.demo.example", "Cargo.toml"),
+                ("git.example.org", ".git/config"),
+                ("github.com", ".git/config"),
+                ("gitlab.example.com", ".git/config"),
+                ("index.corp.example", "Cargo.toml"),
+                ("npm.corp.example", "package.json"),
+                ("patched.example", "Cargo.toml"),
+            ]
+        );
+    }
+
+    #[test]
+    fn malicious_git_config_hosts_are_filtered() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            ".git/config",
+            r#"[remote "a"]
+	url = http://192.168.1.10/repo.git
+[remote "b"]
+	url = git@10.0.0.5:org/repo.git
+[remote "c"]
+	url = https://localhost:8080/x
+[remote "d"]
+	url = https://0x7f.1/x
+[remote "e"]
+	url = https://printer.local/x
+[remote "f"]
+	url = https://[::1]/x
+[remote "g"]
+	url = file:///etc/passwd
+[remote "h"]
+	url = https://evil.example/x
+"#,
+        );
+
+        assert_eq!(
+            hosts(&scan_project_origins(dir.path())),
+            vec![("evil.example", ".git/config")]
+        );
+    }
+
+    #[test]
+    fn oversized_and_symlinked_files_are_not_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let padding = "#".repeat(usize::try_from(MAX_SCAN_BYTES).unwrap());
+        write(
+            dir.path(),
+            "Cargo.toml",
+            &format!("[package]\nrepository = \"https://big.example/\"\n{padding}"),
+        );
+        let outside = tempfile::tempdir().unwrap();
+        write(
+            outside.path(),
+            "package.json",
+            r#"{"homepage": "https://outside.example/"}"#,
+        );
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(
+            outside.path().join("package.json"),
+            dir.path().join("package.json"),
+        )
+        .unwrap();
+
+        assert!(scan_project_origins(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn extend_allowlist_appends_origins() {
+        let origins = vec![ProjectOrigin {
+            host: HostKey::from("github.com"),
+            source: ".git/config",
+        }];
+        assert_eq!(
+            extend_allowlist(&["docs.rs".into()], &origins, "web_fetch", None),
+            vec!["docs.rs".to_string(), "github.com".to_string()]
+        );
+    }
+
+    #[test]
+    fn extend_allowlist_audits_each_addition_with_its_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = AuditLogger::new(
+            crate::config::AuditConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            dir.path().to_path_buf(),
+        )
+        .unwrap();
+        let origins = vec![
+            ProjectOrigin {
+                host: HostKey::from("github.com"),
+                source: ".git/config",
+            },
+            ProjectOrigin {
+                host: HostKey::from("npm.corp.example"),
+                source: "package.json",
+            },
+        ];
+
+        extend_allowlist(&[], &origins, "http_request", Some(&audit));
+
+        let events = crate::security::audit::read_events(&dir.path().join("audit.log")).unwrap();
+        let commands: Vec<_> = events
+            .iter()
+            .map(|event| {
+                assert!(matches!(event.event_type, AuditEventType::ConfigChange));
+                event.action.as_ref().unwrap().command.clone().unwrap()
+            })
+            .collect();
+        assert_eq!(
+            commands,
+            vec![
+                "http_request: allowing github.com from project file .git/config (trust_project_origins)",
+                "http_request: allowing npm.corp.example from project file package.json (trust_project_origins)",
+            ]
+        );
+    }
+}
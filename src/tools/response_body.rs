@@ -0,0 +1,331 @@
+//! Presentation of HTTP response bodies shared by `web_fetch` and `http_request`.
+//!
+//! A blank string in a tool result reads like a failure to the model, so
+//! responses without content are rendered as an explicit
+//! `(empty response, status N)` line instead.
+
+use futures_util::StreamExt;
+use std::fmt;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// A response body with nothing for the model to read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyBody {
+    /// Status 204/205 or a zero-length body.
+    NoContent,
+    /// Only whitespace and byte order marks; `bytes` is how many arrived.
+    Blank { bytes: usize },
+}
+
+impl EmptyBody {
+    /// Statuses that never carry a body, before anything is read.
+    pub fn from_status(status: u16) -> Option<Self> {
+        matches!(status, 204 | 205).then_some(Self::NoContent)
+    }
+
+    /// Classify `body`, returning `None` when it has real content.
+    pub fn classify(status: u16, body: &[u8]) -> Option<Self> {
+        if body.is_empty() {
+            return Some(Self::NoContent);
+        }
+        if let Some(empty) = Self::from_status(status) {
+            return Some(empty);
+        }
+        let mut rest = body.trim_ascii();
+        while let Some(after_bom) = rest.strip_prefix(UTF8_BOM) {
+            rest = after_bom.trim_ascii();
+        }
+        rest.is_empty().then_some(Self::Blank { bytes: body.len() })
+    }
+
+    /// Text shown in place of the body.
+    pub fn describe(self, status: u16) -> String {
+        match self {
+            Self::NoContent => format!("(empty response, status {status})"),
+            Self::Blank { bytes } => format!(
+                "(empty response, status {status}; body was {bytes} bytes of whitespace/BOM)"
+            ),
+        }
+    }
+}
+
+/// A body whose transfer ended before it was complete.
+///
+/// Detectable when the connection closes short of the declared
+/// `Content-Length` or before the final chunk of a chunked body. A body
+/// delimited only by connection close has no end marker to check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TruncatedTransfer {
+    /// `Content-Length`, or `None` for a chunked body.
+    pub declared: Option<u64>,
+    pub received: u64,
+}
+
+impl TruncatedTransfer {
+    /// Compare a body that was read to its end against `declared`.
+    pub fn check(declared: Option<u64>, received: u64) -> Option<Self> {
+        declared
+            .is_some_and(|declared| received < declared)
+            .then_some(Self { declared, received })
+    }
+
+    /// Machine-readable line added to tool output.
+    pub fn marker(self) -> String {
+        let declared = self
+            .declared
+            .map_or_else(|| "unknown".to_string(), |d| d.to_string());
+        format!(
+            "truncated_transfer: true (declared_bytes: {declared}, received_bytes: {})",
+            self.received
+        )
+    }
+}
+
+impl fmt::Display for TruncatedTransfer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.declared {
+            Some(declared) => write!(
+                f,
+                "Truncated transfer: received {} of {declared} bytes declared by Content-Length",
+                self.received
+            ),
+            None => write!(
+                f,
+                "Truncated transfer: chunked body ended after {} bytes without its final chunk",
+                self.received
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TruncatedTransfer {}
+
+/// Read a body, keeping what arrived if the transfer ends early.
+///
+/// With a `limit`, reading stops after `limit + 1` bytes, enough for the
+/// caller to tell that the body was over the limit.
+pub async fn read_body(
+    response: reqwest::Response,
+    limit: Option<usize>,
+) -> (Vec<u8>, Option<TruncatedTransfer>) {
+    let declared = response.content_length();
+    let hard_cap = limit.map_or(usize::MAX, |limit| limit.saturating_add(1));
+    let mut stream = response.bytes_stream();
+    let mut body = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        match chunk {
+            Ok(chunk) => {
+                let take = chunk.len().min(hard_cap - body.len());
+                body.extend_from_slice(&chunk[..take]);
+                if body.len() == hard_cap {
+                    return (body, None);
+                }
+            }
+            Err(e) => {
+                tracing::warn!("response body ended early after {} bytes: {e}", body.len());
+                let received = body.len() as u64;
+                return (body, Some(TruncatedTransfer { declared, received }));
+            }
+        }
+    }
+    let truncated = TruncatedTransfer::check(declared, body.len() as u64);
+    (body, truncated)
+}
+
+/// Decode a body as UTF-8 for display, dropping a leading byte order mark.
+pub fn body_text(body: &[u8]) -> String {
+    String::from_utf8_lossy(body.strip_prefix(UTF8_BOM).unwrap_or(body)).into_owned()
+}
+
+/// The media type of a `Content-Type` value, lowercased and without parameters.
+pub fn mime_essence(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Guess a media type from the first bytes of a body.
+///
+/// Only recognizes formats with unambiguous signatures; `None` means the
+/// body gives no evidence either way.
+pub fn sniff_content_type(body: &[u8]) -> Option<&'static str> {
+    const SIGNATURES: &[(&[u8], &str)] = &[
+        (b"%PDF-", "application/pdf"),
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xFF\xD8\xFF", "image/jpeg"),
+        (b"GIF8", "image/gif"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1F\x8B", "application/gzip"),
+    ];
+    const HTML_TAGS: &[&[u8]] = &[
+        b"<!doctype html",
+        b"<html",
+        b"<head",
+        b"<body",
+        b"<script",
+        b"<title",
+    ];
+
+    if let Some((_, mime)) = SIGNATURES.iter().find(|(magic, _)| body.starts_with(magic)) {
+        return Some(mime);
+    }
+
+    let mut text = body
+        .strip_prefix(UTF8_BOM)
+        .unwrap_or(body)
+        .trim_ascii_start();
+    text = &text[..text.len().min(64)];
+    if HTML_TAGS
+        .iter()
+        .any(|tag| text.len() >= tag.len() && text[..tag.len()].eq_ignore_ascii_case(tag))
+    {
+        return Some("text/html");
+    }
+    if text.starts_with(b"<?xml") {
+        return Some("application/xml");
+    }
+    if text.starts_with(b"{") || text.starts_with(b"[") {
+        return Some("application/json");
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_bodies_without_content() {
+        let cases: &[(&str, u16, &[u8], Option<EmptyBody>)] = &[
+            ("204", 204, b"", Some(EmptyBody::NoContent)),
+            (
+                "205 with stray bytes",
+                205,
+                b"x",
+                Some(EmptyBody::NoContent),
+            ),
+            ("200 empty", 200, b"", Some(EmptyBody::NoContent)),
+            (
+                "BOM only",
+                200,
+                UTF8_BOM,
+                Some(EmptyBody::Blank { bytes: 3 }),
+            ),
+            (
+                "single space",
+                200,
+                b" ",
+                Some(EmptyBody::Blank { bytes: 1 }),
+            ),
+            (
+                "BOM and newlines",
+                200,
+                b"\r\n\xEF\xBB\xBF\n",
+                Some(EmptyBody::Blank { bytes: 6 }),
+            ),
+            ("BOM-prefixed JSON", 200, b"\xEF\xBB\xBF{}", None),
+            ("text", 404, b"not found", None),
+        ];
+
+        for (name, status, body, expected) in cases {
+            assert_eq!(EmptyBody::classify(*status, body), *expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn describes_empty_bodies() {
+        assert_eq!(
+            EmptyBody::NoContent.describe(204),
+            "(empty response, status 204)"
+        );
+        assert_eq!(
+            EmptyBody::Blank { bytes: 3 }.describe(200),
+            "(empty response, status 200; body was 3 bytes of whitespace/BOM)"
+        );
+    }
+
+    #[test]
+    fn body_text_drops_leading_bom_only() {
+        assert_eq!(body_text(b"\xEF\xBB\xBF{\"a\":1}"), "{\"a\":1}");
+        assert_eq!(body_text(b"a\xEF\xBB\xBFb"), "a\u{feff}b");
+    }
+
+    #[test]
+    fn sniffs_unambiguous_signatures_only() {
+        let cases: &[(&[u8], Option<&str>)] = &[
+            (b"  <!DOCTYPE html><html>", Some("text/html")),
+            (b"\xEF\xBB\xBF<HTML lang=en>", Some("text/html")),
+            (b"<?xml version=\"1.0\"?><feed/>", Some("application/xml")),
+            (b"\n{\"ok\":true}", Some("application/json")),
+            (b"[1,2]", Some("application/json")),
+            (b"%PDF-1.7", Some("application/pdf")),
+            (b"\x89PNG\r\n\x1a\nrest", Some("image/png")),
+            (b"plain words", None),
+            (b"<p>fragment</p>", None),
+            (b"", None),
+        ];
+        for (body, expected) in cases {
+            assert_eq!(
+                sniff_content_type(body),
+                *expected,
+                "{}",
+                String::from_utf8_lossy(body)
+            );
+        }
+    }
+
+    #[test]
+    fn truncated_transfer_reports_declared_and_received() {
+        assert_eq!(TruncatedTransfer::check(Some(100), 100), None);
+        assert_eq!(TruncatedTransfer::check(None, 12), None);
+        let short = TruncatedTransfer::check(Some(100), 12).unwrap();
+        assert_eq!(
+            short.to_string(),
+            "Truncated transfer: received 12 of 100 bytes declared by Content-Length"
+        );
+        assert_eq!(
+            short.marker(),
+            "truncated_transfer: true (declared_bytes: 100, received_bytes: 12)"
+        );
+        let chunked = TruncatedTransfer {
+            declared: None,
+            received: 7,
+        };
+        assert!(chunked.to_string().contains("without its final chunk"));
+        assert!(chunked.marker().contains("declared_bytes: unknown"));
+    }
+
+    #[test]
+    fn mime_essence_drops_parameters_and_case() {
+        assert_eq!(
+            mime_essence("Application/JSON; charset=utf-8"),
+            "application/json"
+        );
+        assert_eq!(mime_essence(""), "");
+    }
+
+    #[tokio::test]
+    async fn read_body_stops_one_byte_past_the_limit() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(vec![b'x'; 64 * 1024]))
+            .mount(&server)
+            .await;
+        let url = format!("http://{}/", server.address());
+
+        let (body, truncated) = read_body(reqwest::get(&url).await.unwrap(), Some(10)).await;
+        assert_eq!(body.len(), 11);
+        assert_eq!(truncated, None);
+
+        let (body, truncated) = read_body(reqwest::get(&url).await.unwrap(), None).await;
+        assert_eq!(body.len(), 64 * 1024);
+        assert_eq!(truncated, None);
+    }
+}
@@ -0,0 +1,325 @@
+//! Retry guidance appended to `web_fetch` and `http_request` errors.
+//!
+//! Models tend to retry failures that cannot succeed (policy denials, 404s,
+//! bad schemes) and give up on ones that would (timeouts, 503s). Every error
+//! these tools return therefore ends with one sentence, rendered from a
+//! [`RetryGuidance`], that says whether and how to try again.
+
+use super::host_cooldown::{CoolingDown, parse_retry_after_secs};
+use super::response_body::TruncatedTransfer;
+use super::url_validation::UrlValidationError;
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+
+/// What the caller should do next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestedAction {
+    UseDifferentUrl,
+    AskOperatorForApproval,
+    ReduceRequestSize,
+    WaitAndRetry,
+    DoNotRetry,
+}
+
+/// Whether and when a failed request is worth repeating.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryGuidance {
+    pub retryable: bool,
+    pub retry_after_seconds: Option<u64>,
+    pub suggested_action: SuggestedAction,
+}
+
+impl RetryGuidance {
+    /// The request cannot succeed as sent.
+    pub const DO_NOT_RETRY: Self = Self::final_action(SuggestedAction::DoNotRetry);
+    /// The target itself is refused; another URL may work.
+    pub const USE_DIFFERENT_URL: Self = Self::final_action(SuggestedAction::UseDifferentUrl);
+    /// Only the operator can lift the refusal.
+    pub const ASK_OPERATOR: Self = Self::final_action(SuggestedAction::AskOperatorForApproval);
+
+    const fn final_action(suggested_action: SuggestedAction) -> Self {
+        Self {
+            retryable: false,
+            retry_after_seconds: None,
+            suggested_action,
+        }
+    }
+
+    /// A transient failure; retry after `seconds` when known.
+    pub const fn wait_and_retry(seconds: Option<u64>) -> Self {
+        Self {
+            retryable: true,
+            retry_after_seconds: seconds,
+            suggested_action: SuggestedAction::WaitAndRetry,
+        }
+    }
+
+    /// Guidance for a URL rejected by policy.
+    ///
+    /// A host missing from the allowlist points at the operator when
+    /// `approval_enabled`, since a supervised session has someone who can
+    /// add it to `allowed_domains`.
+    pub fn for_url_error(err: &UrlValidationError, approval_enabled: bool) -> Self {
+        match err {
+            UrlValidationError::NotInAllowlist { .. } if approval_enabled => Self::ASK_OPERATOR,
+            UrlValidationError::NoAllowlistConfigured => Self::ASK_OPERATOR,
+            UrlValidationError::ResolutionFailed { .. } => Self::wait_and_retry(None),
+            _ => Self::USE_DIFFERENT_URL,
+        }
+    }
+
+    /// Guidance for a non-success HTTP status, honoring `Retry-After`.
+    pub fn for_status(status: StatusCode, headers: &HeaderMap) -> Self {
+        match status.as_u16() {
+            408 | 425 | 429 | 500 | 502 | 503 | 504 => {
+                Self::wait_and_retry(parse_retry_after_secs(headers))
+            }
+            413 | 414 | 431 => Self {
+                retryable: true,
+                retry_after_seconds: None,
+                suggested_action: SuggestedAction::ReduceRequestSize,
+            },
+            404 | 410 => Self::USE_DIFFERENT_URL,
+            _ => Self::DO_NOT_RETRY,
+        }
+    }
+
+    /// Guidance for an error raised while sending a request.
+    ///
+    /// Timeouts, connection failures, truncated transfers and host cooldowns
+    /// are transient; URL policy failures, such as a rejected redirect hop,
+    /// get [`for_url_error`](Self::for_url_error) with `approval_enabled`;
+    /// everything else (interceptor denials, malformed requests) repeats
+    /// identically.
+    pub fn for_error(err: &anyhow::Error, approval_enabled: bool) -> Self {
+        if let Some(cooling) = err.downcast_ref::<CoolingDown>() {
+            return Self::wait_and_retry(Some(cooling.remaining.as_secs().max(1)));
+        }
+        if err.downcast_ref::<TruncatedTransfer>().is_some() {
+            return Self::wait_and_retry(None);
+        }
+        if let Some(url_err) = err.downcast_ref::<UrlValidationError>() {
+            return Self::for_url_error(url_err, approval_enabled);
+        }
+        match err.downcast_ref::<reqwest::Error>() {
+            Some(e) if e.is_timeout() || e.is_connect() || e.is_request() || e.is_body() => {
+                Self::wait_and_retry(None)
+            }
+            _ => Self::DO_NOT_RETRY,
+        }
+    }
+
+    /// The guidance as one short sentence.
+    pub fn sentence(self) -> String {
+        match self.suggested_action {
+            SuggestedAction::UseDifferentUrl => {
+                "Do not retry this URL; use a different one.".into()
+            }
+            SuggestedAction::AskOperatorForApproval => {
+                "Do not retry; ask the operator to add this host to allowed_domains.".into()
+            }
+            SuggestedAction::ReduceRequestSize => "Reduce the request size, then retry.".into(),
+            SuggestedAction::WaitAndRetry => match self.retry_after_seconds {
+                Some(seconds) => format!("Wait {seconds}s, then retry."),
+                None => "Wait a while, then retry.".into(),
+            },
+            SuggestedAction::DoNotRetry => "Do not retry this request.".into(),
+        }
+    }
+
+    /// `message` with the guidance appended as its final sentence.
+    pub fn append_to(self, message: &str) -> String {
+        let message = message.trim_end().trim_end_matches('.');
+        format!("{message}. {}", self.sentence())
+    }
+}
+
+/// Error text for an action refused because autonomy is read-only.
+pub fn read_only_error() -> String {
+    RetryGuidance::DO_NOT_RETRY.append_to("Action blocked: autonomy is read-only")
+}
+
+/// Error text for an action refused by the hourly action budget.
+pub fn rate_limited_error() -> String {
+    RetryGuidance::wait_and_retry(None).append_to("Action blocked: rate limit exceeded")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::url_validation::HostKey;
+    use std::time::Duration;
+
+    fn retry_after(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(reqwest::header::RETRY_AFTER, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn guidance_matrix() {
+        use SuggestedAction::{
+            AskOperatorForApproval, DoNotRetry, ReduceRequestSize, UseDifferentUrl, WaitAndRetry,
+        };
+
+        let not_allowlisted = UrlValidationError::NotInAllowlist {
+            host: "other.example".into(),
+        };
+        let cooling: anyhow::Error = CoolingDown {
+            host: HostKey::from("api.example.com"),
+            remaining: Duration::from_millis(41_500),
+        }
+        .into();
+
+        let cases: Vec<(&str, RetryGuidance, bool, Option<u64>, SuggestedAction)> = vec![
+            (
+                "scheme",
+                RetryGuidance::for_url_error(
+                    &UrlValidationError::DisallowedScheme {
+                        required: crate::tools::url_validation::SchemeConstraint::HttpOrHttps,
+                    },
+                    true,
+                ),
+                false,
+                None,
+                UseDifferentUrl,
+            ),
+            (
+                "private host",
+                RetryGuidance::for_url_error(
+                    &UrlValidationError::PrivateOrLocalHost {
+                        host: "10.0.0.1".into(),
+                    },
+                    true,
+                ),
+                false,
+                None,
+                UseDifferentUrl,
+            ),
+            (
+                "allowlist, unsupervised",
+                RetryGuidance::for_url_error(&not_allowlisted, false),
+                false,
+                None,
+                UseDifferentUrl,
+            ),
+            (
+                "allowlist, supervised",
+                RetryGuidance::for_url_error(&not_allowlisted, true),
+                false,
+                None,
+                AskOperatorForApproval,
+            ),
+            (
+                "allowlist on a redirect hop, supervised",
+                RetryGuidance::for_error(
+                    &anyhow::Error::new(not_allowlisted.clone())
+                        .context("Blocked redirect at hop 1"),
+                    true,
+                ),
+                false,
+                None,
+                AskOperatorForApproval,
+            ),
+            (
+                "dns failure",
+                RetryGuidance::for_url_error(
+                    &UrlValidationError::ResolutionFailed {
+                        host: "example.com".into(),
+                        reason: "timed out".into(),
+                    },
+                    false,
+                ),
+                true,
+                None,
+                WaitAndRetry,
+            ),
+            (
+                "404",
+                RetryGuidance::for_status(StatusCode::NOT_FOUND, &HeaderMap::new()),
+                false,
+                None,
+                UseDifferentUrl,
+            ),
+            (
+                "401",
+                RetryGuidance::for_status(StatusCode::UNAUTHORIZED, &HeaderMap::new()),
+                false,
+                None,
+                DoNotRetry,
+            ),
+            (
+                "413",
+                RetryGuidance::for_status(StatusCode::PAYLOAD_TOO_LARGE, &HeaderMap::new()),
+                true,
+                None,
+                ReduceRequestSize,
+            ),
+            (
+                "503 with Retry-After",
+                RetryGuidance::for_status(StatusCode::SERVICE_UNAVAILABLE, &retry_after("120")),
+                true,
+                Some(120),
+                WaitAndRetry,
+            ),
+            (
+                "429 without Retry-After",
+                RetryGuidance::for_status(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new()),
+                true,
+                None,
+                WaitAndRetry,
+            ),
+            (
+                "cooldown",
+                RetryGuidance::for_error(&cooling, false),
+                true,
+                Some(41),
+                WaitAndRetry,
+            ),
+            (
+                "interceptor denial",
+                RetryGuidance::for_error(&anyhow::anyhow!("denied by pre-send interceptor"), true),
+                false,
+                None,
+                DoNotRetry,
+            ),
+        ];
+
+        for (name, guidance, retryable, after, action) in cases {
+            assert_eq!(guidance.retryable, retryable, "{name}");
+            assert_eq!(guidance.retry_after_seconds, after, "{name}");
+            assert_eq!(guidance.suggested_action, action, "{name}");
+        }
+    }
+
+    #[tokio::test]
+    async fn transport_failures_are_retryable() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+
+        let err: anyhow::Error = reqwest::Client::new()
+            .get(format!("http://{addr}/"))
+            .send()
+            .await
+            .unwrap_err()
+            .into();
+        assert_eq!(
+            RetryGuidance::for_error(&err, false),
+            RetryGuidance::wait_and_retry(None)
+        );
+    }
+
+    #[test]
+    fn guidance_is_the_final_sentence() {
+        assert_eq!(
+            RetryGuidance::USE_DIFFERENT_URL.append_to("HTTP 404 Not Found"),
+            "HTTP 404 Not Found. Do not retry this URL; use a different one."
+        );
+        assert_eq!(
+            RetryGuidance::wait_and_retry(Some(30))
+                .append_to("Action blocked: rate limit exceeded."),
+            "Action blocked: rate limit exceeded. Wait 30s, then retry."
+        );
+    }
+}
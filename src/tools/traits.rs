@@ -1,5 +1,6 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Result of a tool execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,6 +10,76 @@ pub struct ToolResult {
     pub error: Option<String>,
 }
 
+/// Pre-execution estimate of what a tool call would cost.
+///
+/// Computed from the arguments and configured caps alone, without network I/O.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostEstimate {
+    /// Requests issued when the call succeeds without redirects or fallbacks
+    pub request_count: usize,
+    /// Upper bound on requests, counting redirect hops and fallbacks
+    pub max_request_count: usize,
+    /// Upper bound on response bytes read; `None` when unbounded
+    pub worst_case_bytes: Option<u64>,
+    /// Error the call would fail with before sending anything, if any
+    pub blocked: Option<String>,
+    /// Whether the call is refused until an operator approves it, such as a
+    /// host outside the allowlist in supervised mode
+    pub may_prompt_approval: bool,
+    /// Local state that settles the call without a request, if any
+    pub short_circuit: Option<ShortCircuit>,
+}
+
+impl CostEstimate {
+    /// Estimate for a call that would be refused before any request is sent.
+    pub fn blocked(reason: impl Into<String>) -> Self {
+        Self {
+            blocked: Some(reason.into()),
+            ..Self::default()
+        }
+    }
+}
+
+/// Why a call would be settled without sending a request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum ShortCircuit {
+    /// The host is cooling down after a 429 for longer than the request
+    /// timeout, so the call fails without waiting.
+    Cooldown { remaining: Duration },
+}
+
+/// A call refused by a tool's preflight checks, before anything is sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Refusal {
+    /// Error returned to the model, retry guidance included
+    pub message: String,
+    /// Only an operator can lift the refusal
+    pub needs_approval: bool,
+    pub short_circuit: Option<ShortCircuit>,
+}
+
+impl From<String> for Refusal {
+    fn from(message: String) -> Self {
+        Self {
+            message,
+            needs_approval: false,
+            short_circuit: None,
+        }
+    }
+}
+
+impl From<Refusal> for CostEstimate {
+    fn from(refusal: Refusal) -> Self {
+        Self {
+            may_prompt_approval: refusal.needs_approval,
+            short_circuit: refusal.short_circuit,
+            ..Self::blocked(refusal.message)
+        }
+    }
+}
+
 /// Description of a tool for the LLM
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolSpec {
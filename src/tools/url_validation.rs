@@ -0,0 +1,1101 @@
+//! Shared URL validation policy for network-facing tools.
+//!
+//! `web_fetch`, `http_request`, and `browser_open` all validate model-supplied
+//! URLs against the same rules: scheme constraint, userinfo/IPv6 rejection,
+//! domain allow/block lists, and SSRF protection for local/private hosts.
+//! [`UrlPolicy::validate`] is the single entry point for those rules so the
+//! tools cannot drift apart; failures are reported as [`UrlValidationError`]
+//! variants that each tool maps to its own operator-facing message.
+
+use regex::Regex;
+use std::borrow::Cow;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::LazyLock;
+
+/// Replaces a non-empty URL fragment wherever URLs are logged.
+pub const REDACTED_FRAGMENT: &str = "#[redacted]";
+
+/// Which URL schemes a policy accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemeConstraint {
+    /// Accept both `http://` and `https://`.
+    HttpOrHttps,
+    /// Accept `https://` only.
+    HttpsOnly,
+}
+
+impl SchemeConstraint {
+    fn allows(self, scheme: &str) -> bool {
+        match self {
+            Self::HttpOrHttps => scheme == "http" || scheme == "https",
+            Self::HttpsOnly => scheme == "https",
+        }
+    }
+}
+
+impl std::fmt::Display for SchemeConstraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HttpOrHttps => f.write_str("http:// and https://"),
+            Self::HttpsOnly => f.write_str("https://"),
+        }
+    }
+}
+
+/// Reasons a URL can be rejected by [`UrlPolicy::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum UrlValidationError {
+    #[error("URL cannot be empty")]
+    EmptyUrl,
+    #[error("URL cannot contain whitespace")]
+    ContainsWhitespace,
+    #[error("Only {required} URLs are allowed")]
+    DisallowedScheme { required: SchemeConstraint },
+    #[error("URL userinfo is not allowed")]
+    UserinfoNotAllowed,
+    #[error("IPv6 hosts are not supported")]
+    Ipv6NotSupported,
+    #[error("URL must include a host")]
+    MissingHost,
+    #[error("Host '{host}' is numeric but not a valid IPv4 address")]
+    InvalidNumericHost { host: String },
+    #[error("Host '{host}' is not a valid hostname")]
+    MalformedHost { host: String },
+    #[error("No allowed_domains are configured")]
+    NoAllowlistConfigured,
+    #[error("Host '{host}' is in the domain blocklist")]
+    BlockedDomain { host: String },
+    #[error("Blocked local/private host: {host}")]
+    PrivateOrLocalHost { host: String },
+    #[error("Host '{host}' is not in the domain allowlist")]
+    NotInAllowlist { host: String },
+    #[error("Failed to resolve host '{host}': {reason}")]
+    ResolutionFailed { host: String, reason: String },
+    #[error("Blocked host '{host}' resolved to non-global address {ip}")]
+    ResolvesToNonGlobal { host: String, ip: IpAddr },
+}
+
+/// Canonical host used for every host comparison and host-keyed map.
+///
+/// Canonicalization trims whitespace, strips IPv6 brackets and trailing dots,
+/// lowercases, maps internationalized labels to their ASCII (punycode) form,
+/// and renders IP literals in standard notation. Two textual forms of the same
+/// host therefore always produce equal keys; compare `HostKey`s rather than
+/// raw strings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HostKey(String);
+
+impl HostKey {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    /// True when this host equals `domain` or is a subdomain of it.
+    /// A `*` domain matches every host.
+    pub fn is_within(&self, domain: &HostKey) -> bool {
+        domain.0 == "*"
+            || self.0 == domain.0
+            || self
+                .0
+                .strip_suffix(domain.0.as_str())
+                .is_some_and(|prefix| prefix.ends_with('.'))
+    }
+}
+
+impl From<&str> for HostKey {
+    fn from(raw: &str) -> Self {
+        let trimmed = raw.trim();
+        let bare = trimmed
+            .strip_prefix('[')
+            .and_then(|h| h.strip_suffix(']'))
+            .unwrap_or(trimmed)
+            .trim_end_matches('.');
+
+        if let Ok(ip) = bare.parse::<IpAddr>() {
+            return Self(ip.to_string());
+        }
+
+        if let NumericHost::Ipv4(ip) = classify_numeric_host(bare) {
+            return Self(ip.to_string());
+        }
+
+        if !bare.is_ascii() {
+            // Delegate IDNA mapping (case folding, full-width dots, punycode)
+            // to the WHATWG host parser. Only non-ASCII input takes this path
+            // so ASCII hosts are never reinterpreted.
+            if let Some(host) = reqwest::Url::parse(&format!("http://{bare}/"))
+                .ok()
+                .and_then(|url| url.host_str().map(|h| h.trim_end_matches('.').to_string()))
+            {
+                return Self(host);
+            }
+        }
+
+        Self(bare.to_ascii_lowercase())
+    }
+}
+
+impl From<String> for HostKey {
+    fn from(raw: String) -> Self {
+        Self::from(raw.as_str())
+    }
+}
+
+impl std::fmt::Display for HostKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A URL that passed [`UrlPolicy::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatedUrl {
+    url: String,
+    scheme: &'static str,
+    host: HostKey,
+}
+
+impl ValidatedUrl {
+    /// The trimmed URL as supplied by the caller.
+    pub fn as_str(&self) -> &str {
+        &self.url
+    }
+
+    /// Lowercase scheme (`http` or `https`).
+    pub fn scheme(&self) -> &'static str {
+        self.scheme
+    }
+
+    /// Canonical host (see [`HostKey`]), without port.
+    pub fn host(&self) -> &str {
+        self.host.as_str()
+    }
+
+    pub fn host_key(&self) -> &HostKey {
+        &self.host
+    }
+
+    pub fn into_string(self) -> String {
+        self.url
+    }
+
+    // Fragment policy: fragments never affect validation (hosts are
+    // extracted before the `#`). They are kept for anything that hands the
+    // URL back to a user agent (browser_open, section anchors), stripped from
+    // cache and dedup keys, and redacted in logs because SPA routes carry
+    // secrets there (`#access_token=...`).
+
+    /// Fragment without the leading `#`, if present and non-empty.
+    pub fn fragment(&self) -> Option<&str> {
+        self.url
+            .split_once('#')
+            .map(|(_, fragment)| fragment)
+            .filter(|fragment| !fragment.is_empty())
+    }
+
+    /// The URL with any fragment removed — the form to use for cache keys.
+    pub fn without_fragment(&self) -> &str {
+        self.url.split_once('#').map_or(&self.url, |(base, _)| base)
+    }
+
+    /// The URL as it should appear in logs and audit records.
+    ///
+    /// A non-empty fragment is replaced by [`REDACTED_FRAGMENT`] unless
+    /// `log_fragments` is set (a debug-only override).
+    pub fn for_log(&self, log_fragments: bool) -> Cow<'_, str> {
+        url_for_log(&self.url, log_fragments)
+    }
+}
+
+/// [`ValidatedUrl::for_log`] for a URL or reference that has not been
+/// validated, such as a raw redirect `Location`.
+pub fn url_for_log(url: &str, log_fragments: bool) -> Cow<'_, str> {
+    match url.split_once('#') {
+        _ if log_fragments => Cow::Borrowed(url),
+        Some((base, "")) => Cow::Borrowed(base),
+        Some((base, _)) => Cow::Owned(format!("{base}{REDACTED_FRAGMENT}")),
+        None => Cow::Borrowed(url),
+    }
+}
+
+impl std::fmt::Display for ValidatedUrl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.url)
+    }
+}
+
+static URL_FRAGMENT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)(\bhttps?://[^\s#"'<>]*)#([^\s"'<>]*)"#).unwrap());
+
+/// Apply the log side of the fragment policy to free text: every `http(s)`
+/// URL in `text` has its fragment replaced by [`REDACTED_FRAGMENT`] (or just
+/// dropped when empty).
+pub fn redact_url_fragments(text: &str) -> Cow<'_, str> {
+    URL_FRAGMENT_RE.replace_all(text, |caps: &regex::Captures<'_>| {
+        if caps[2].is_empty() {
+            caps[1].to_string()
+        } else {
+            format!("{}{REDACTED_FRAGMENT}", &caps[1])
+        }
+    })
+}
+
+/// URL acceptance rules shared by the network tools.
+///
+/// Domain lists are normalized to [`HostKey`]s on construction.
+/// `blocked_domains` always wins over every allow rule.
+#[derive(Debug, Clone)]
+pub struct UrlPolicy {
+    scheme: SchemeConstraint,
+    allowed_domains: Vec<HostKey>,
+    blocked_domains: Vec<HostKey>,
+    allowed_private_hosts: Vec<HostKey>,
+    allow_private_hosts: bool,
+    resolve_dns: bool,
+}
+
+impl UrlPolicy {
+    pub fn new(scheme: SchemeConstraint, allowed_domains: Vec<String>) -> Self {
+        Self {
+            scheme,
+            allowed_domains: normalize_host_keys(allowed_domains),
+            blocked_domains: Vec::new(),
+            allowed_private_hosts: Vec::new(),
+            allow_private_hosts: false,
+            resolve_dns: false,
+        }
+    }
+
+    /// Hosts (and their subdomains) that are always rejected.
+    pub fn with_blocked_domains(mut self, domains: Vec<String>) -> Self {
+        self.blocked_domains = normalize_host_keys(domains);
+        self
+    }
+
+    /// Specific local/private hosts that bypass the SSRF block and the allowlist.
+    pub fn with_allowed_private_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.allowed_private_hosts = normalize_host_keys(hosts);
+        self
+    }
+
+    /// Permit any local/private host, still subject to the allowlist.
+    pub fn with_allow_private_hosts(mut self, allow: bool) -> Self {
+        self.allow_private_hosts = allow;
+        self
+    }
+
+    /// Resolve public hostnames and reject any that map to non-global addresses.
+    pub fn with_dns_check(mut self, enabled: bool) -> Self {
+        self.resolve_dns = enabled;
+        self
+    }
+
+    pub fn scheme(&self) -> SchemeConstraint {
+        self.scheme
+    }
+
+    pub fn allowed_domains(&self) -> &[HostKey] {
+        &self.allowed_domains
+    }
+
+    pub fn validate(&self, raw_url: &str) -> Result<ValidatedUrl, UrlValidationError> {
+        self.check(raw_url, self.resolve_dns)
+    }
+
+    /// Apply every rule except the DNS check, so the verdict needs no network I/O.
+    pub fn validate_offline(&self, raw_url: &str) -> Result<ValidatedUrl, UrlValidationError> {
+        self.check(raw_url, false)
+    }
+
+    fn check(&self, raw_url: &str, resolve_dns: bool) -> Result<ValidatedUrl, UrlValidationError> {
+        let url = raw_url.trim();
+
+        if url.is_empty() {
+            return Err(UrlValidationError::EmptyUrl);
+        }
+
+        if url.chars().any(char::is_whitespace) {
+            return Err(UrlValidationError::ContainsWhitespace);
+        }
+
+        let scheme = if url.starts_with("https://") {
+            "https"
+        } else if url.starts_with("http://") {
+            "http"
+        } else {
+            ""
+        };
+        if !self.scheme.allows(scheme) {
+            return Err(UrlValidationError::DisallowedScheme {
+                required: self.scheme,
+            });
+        }
+
+        if self.allowed_domains.is_empty() {
+            return Err(UrlValidationError::NoAllowlistConfigured);
+        }
+
+        let host = extract_host(url)?;
+
+        if host_key_matches(&host, &self.blocked_domains) {
+            return Err(UrlValidationError::BlockedDomain {
+                host: host.into_string(),
+            });
+        }
+
+        let is_private = is_private_or_local_host(host.as_str());
+        let private_host_allowed =
+            is_private && host_key_matches(&host, &self.allowed_private_hosts);
+
+        if is_private && !private_host_allowed && !self.allow_private_hosts {
+            return Err(UrlValidationError::PrivateOrLocalHost {
+                host: host.into_string(),
+            });
+        }
+
+        if private_host_allowed {
+            tracing::warn!("allowing private/local host '{host}' via allowed_private_hosts");
+        } else {
+            if !host_key_matches(&host, &self.allowed_domains) {
+                return Err(UrlValidationError::NotInAllowlist {
+                    host: host.into_string(),
+                });
+            }
+            if resolve_dns && !is_private {
+                validate_resolved_host_is_public(host.as_str())?;
+            }
+        }
+
+        Ok(ValidatedUrl {
+            url: url.to_string(),
+            scheme,
+            host,
+        })
+    }
+}
+
+// ── Free-function helpers ────────────────────────────────────────
+
+/// Normalize configured domain entries into sorted, deduplicated host keys.
+pub fn normalize_host_keys(domains: Vec<String>) -> Vec<HostKey> {
+    let mut normalized = domains
+        .into_iter()
+        .filter_map(|d| normalize_domain(&d))
+        .map(HostKey)
+        .collect::<Vec<_>>();
+    normalized.sort_unstable();
+    normalized.dedup();
+    normalized
+}
+
+pub fn normalize_allowed_domains(domains: Vec<String>) -> Vec<String> {
+    normalize_host_keys(domains)
+        .into_iter()
+        .map(HostKey::into_string)
+        .collect()
+}
+
+pub fn normalize_domain(raw: &str) -> Option<String> {
+    let mut d = raw.trim().to_lowercase();
+    if d.is_empty() {
+        return None;
+    }
+
+    if let Some(stripped) = d.strip_prefix("https://") {
+        d = stripped.to_string();
+    } else if let Some(stripped) = d.strip_prefix("http://") {
+        d = stripped.to_string();
+    }
+
+    if let Some((host, _)) = d.split_once('/') {
+        d = host.to_string();
+    }
+
+    d = d.trim_start_matches('.').trim_end_matches('.').to_string();
+
+    if let Some((host, _)) = d.split_once(':') {
+        d = host.to_string();
+    }
+
+    if d.is_empty() || d.chars().any(char::is_whitespace) {
+        return None;
+    }
+
+    Some(HostKey::from(d).into_string())
+}
+
+/// Extract the canonical host from an `http://` or `https://` URL.
+///
+/// The host must be the one the HTTP client will connect to. Characters that
+/// a WHATWG parser treats specially inside the authority (`\` ends it,
+/// `%` is decoded) are rejected outright, and the result is cross-checked
+/// against `reqwest`'s own parse of the URL.
+pub fn extract_host(url: &str) -> Result<HostKey, UrlValidationError> {
+    let rest = url
+        .strip_prefix("http://")
+        .or_else(|| url.strip_prefix("https://"))
+        .ok_or(UrlValidationError::DisallowedScheme {
+            required: SchemeConstraint::HttpOrHttps,
+        })?;
+
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+
+    if authority.is_empty() {
+        return Err(UrlValidationError::MissingHost);
+    }
+
+    if authority.contains('@') {
+        return Err(UrlValidationError::UserinfoNotAllowed);
+    }
+
+    if authority.starts_with('[') {
+        return Err(UrlValidationError::Ipv6NotSupported);
+    }
+
+    let raw_host = authority.split(':').next().unwrap_or_default();
+    if !raw_host.chars().all(is_hostname_char) {
+        return Err(UrlValidationError::MalformedHost {
+            host: raw_host.to_string(),
+        });
+    }
+
+    let host = HostKey::from(raw_host);
+
+    if host.as_str().is_empty() {
+        return Err(UrlValidationError::MissingHost);
+    }
+
+    if classify_numeric_host(host.as_str()) == NumericHost::Invalid {
+        return Err(UrlValidationError::InvalidNumericHost {
+            host: host.into_string(),
+        });
+    }
+
+    // Anything the client would parse differently is not the host we checked.
+    let client_host = reqwest::Url::parse(url)
+        .ok()
+        .map(|parsed| parsed.host_str().map(HostKey::from));
+    if client_host.is_some_and(|client_host| client_host.as_ref() != Some(&host)) {
+        return Err(UrlValidationError::MalformedHost {
+            host: raw_host.to_string(),
+        });
+    }
+
+    Ok(host)
+}
+
+/// Letters, digits, `-`, `_` and `.`; non-ASCII is left to IDNA mapping.
+fn is_hostname_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') || !c.is_ascii()
+}
+
+/// How a host whose last label is numeric decodes as IPv4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumericHost {
+    /// An ordinary DNS name.
+    NotNumeric,
+    /// One of the legacy spellings `inet_aton` and WHATWG URL parsers accept:
+    /// dword (`2130706433`), hex (`0x7f000001`), octal or zero-padded octets
+    /// (`0177.0.0.1`, `127.000.000.001`), short forms (`127.1`) and mixes of
+    /// these (`0x7f.0.0.1`).
+    Ipv4(Ipv4Addr),
+    /// Looks numeric but does not decode; resolvers disagree on what these
+    /// mean, so they must never reach DNS.
+    Invalid,
+}
+
+/// Classify `host` the way a WHATWG URL parser would: a host whose last
+/// label is a number is an IPv4 address, never a DNS name.
+///
+/// `IpAddr::from_str` only accepts canonical dotted-quad, so without this the
+/// alternate spellings would be treated as hostnames and handed to
+/// `getaddrinfo`, which on glibc happily resolves them to loopback.
+pub fn classify_numeric_host(host: &str) -> NumericHost {
+    let host = host.trim_end_matches('.');
+    if host.contains(':') {
+        return NumericHost::NotNumeric;
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    if !labels.last().is_some_and(|label| is_numeric_label(label)) {
+        return NumericHost::NotNumeric;
+    }
+    if labels.len() > 4 {
+        return NumericHost::Invalid;
+    }
+
+    let Some(values) = labels
+        .iter()
+        .map(|label| parse_numeric_label(label))
+        .collect::<Option<Vec<u64>>>()
+    else {
+        return NumericHost::Invalid;
+    };
+
+    let (last, leading) = values.split_last().expect("at least one label");
+    if leading.iter().any(|value| *value > 255) {
+        return NumericHost::Invalid;
+    }
+    let last_bits = 8 * (5 - values.len());
+    if *last >= 1_u64 << last_bits {
+        return NumericHost::Invalid;
+    }
+
+    let prefix = leading
+        .iter()
+        .enumerate()
+        .fold(0_u64, |acc, (i, value)| acc | (value << (24 - 8 * i)));
+    u32::try_from(prefix | last).map_or(NumericHost::Invalid, |bits| {
+        NumericHost::Ipv4(Ipv4Addr::from(bits))
+    })
+}
+
+fn is_numeric_label(label: &str) -> bool {
+    match label
+        .strip_prefix("0x")
+        .or_else(|| label.strip_prefix("0X"))
+    {
+        Some(hex) => hex.chars().all(|c| c.is_ascii_hexdigit()),
+        None => !label.is_empty() && label.chars().all(|c| c.is_ascii_digit()),
+    }
+}
+
+fn parse_numeric_label(label: &str) -> Option<u64> {
+    let (digits, radix) = if let Some(hex) = label
+        .strip_prefix("0x")
+        .or_else(|| label.strip_prefix("0X"))
+    {
+        (hex, 16)
+    } else if label.len() > 1 && label.starts_with('0') {
+        (&label[1..], 8)
+    } else {
+        (label, 10)
+    };
+    if digits.is_empty() {
+        return Some(0);
+    }
+    // Anything longer cannot fit in 32 bits and would overflow u64 parsing.
+    if digits.len() > 32 {
+        return None;
+    }
+    u64::from_str_radix(digits, radix).ok()
+}
+
+/// True when `host` equals or is a subdomain of any entry in `domains`.
+pub fn host_key_matches(host: &HostKey, domains: &[HostKey]) -> bool {
+    domains.iter().any(|domain| host.is_within(domain))
+}
+
+pub fn host_matches_allowlist(host: &str, allowed_domains: &[String]) -> bool {
+    let host = HostKey::from(host);
+    allowed_domains
+        .iter()
+        .any(|domain| host.is_within(&HostKey::from(domain.as_str())))
+}
+
+pub fn is_private_or_local_host(host: &str) -> bool {
+    // Strip brackets from IPv6 addresses like [::1]
+    let bare = host
+        .strip_prefix('[')
+        .and_then(|h| h.strip_suffix(']'))
+        .unwrap_or(host);
+
+    let has_local_tld = bare
+        .rsplit('.')
+        .next()
+        .is_some_and(|label| label == "local");
+
+    if bare == "localhost" || bare.ends_with(".localhost") || has_local_tld {
+        return true;
+    }
+
+    if let Ok(ip) = bare.parse::<IpAddr>() {
+        return is_non_global_ip(ip);
+    }
+
+    match classify_numeric_host(bare) {
+        NumericHost::Ipv4(ip) => is_non_global_ip(IpAddr::V4(ip)),
+        NumericHost::Invalid => true,
+        NumericHost::NotNumeric => false,
+    }
+}
+
+#[cfg(not(test))]
+fn validate_resolved_host_is_public(host: &str) -> Result<(), UrlValidationError> {
+    use std::net::ToSocketAddrs;
+
+    let ips = (host, 0)
+        .to_socket_addrs()
+        .map_err(|e| UrlValidationError::ResolutionFailed {
+            host: host.to_string(),
+            reason: e.to_string(),
+        })?
+        .map(|addr| addr.ip())
+        .collect::<Vec<_>>();
+
+    validate_resolved_ips_are_public(host, &ips)
+}
+
+#[cfg(test)]
+fn validate_resolved_host_is_public(_host: &str) -> Result<(), UrlValidationError> {
+    // DNS checks are covered by validate_resolved_ips_are_public unit tests.
+    Ok(())
+}
+
+pub fn validate_resolved_ips_are_public(
+    host: &str,
+    ips: &[IpAddr],
+) -> Result<(), UrlValidationError> {
+    if ips.is_empty() {
+        return Err(UrlValidationError::ResolutionFailed {
+            host: host.to_string(),
+            reason: "no addresses returned".into(),
+        });
+    }
+
+    if let Some(ip) = ips.iter().copied().find(|ip| is_non_global_ip(*ip)) {
+        return Err(UrlValidationError::ResolvesToNonGlobal {
+            host: host.to_string(),
+            ip,
+        });
+    }
+
+    Ok(())
+}
+
+fn is_non_global_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_non_global_v4(v4),
+        IpAddr::V6(v6) => is_non_global_v6(v6),
+    }
+}
+
+/// Returns true if the IPv4 address is not globally routable.
+fn is_non_global_v4(v4: Ipv4Addr) -> bool {
+    let [a, b, c, _] = v4.octets();
+    v4.is_loopback()                       // 127.0.0.0/8
+        || v4.is_private()                 // 10/8, 172.16/12, 192.168/16
+        || v4.is_link_local()              // 169.254.0.0/16
+        || v4.is_unspecified()             // 0.0.0.0
+        || v4.is_broadcast()               // 255.255.255.255
+        || v4.is_multicast()               // 224.0.0.0/4
+        || (a == 100 && (64..=127).contains(&b)) // Shared address space (RFC 6598)
+        || a >= 240                        // Reserved (240.0.0.0/4, except broadcast)
+        || (a == 192 && b == 0 && (c == 0 || c == 2)) // IETF assignments + TEST-NET-1
+        || (a == 198 && b == 51)           // Documentation (198.51.100.0/24)
+        || (a == 203 && b == 0)            // Documentation (203.0.113.0/24)
+        || (a == 198 && (18..=19).contains(&b)) // Benchmarking (198.18.0.0/15)
+}
+
+/// Returns true if the IPv6 address is not globally routable.
+fn is_non_global_v6(v6: Ipv6Addr) -> bool {
+    let segs = v6.segments();
+    v6.is_loopback()                       // ::1
+        || v6.is_unspecified()             // ::
+        || v6.is_multicast()               // ff00::/8
+        || (segs[0] & 0xfe00) == 0xfc00    // Unique-local (fc00::/7)
+        || (segs[0] & 0xffc0) == 0xfe80    // Link-local (fe80::/10)
+        || (segs[0] == 0x2001 && segs[1] == 0x0db8) // Documentation (2001:db8::/32)
+        || v6.to_ipv4_mapped().is_some_and(is_non_global_v4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allowed: &[&str]) -> UrlPolicy {
+        UrlPolicy::new(
+            SchemeConstraint::HttpOrHttps,
+            allowed.iter().map(|d| (*d).to_string()).collect(),
+        )
+    }
+
+    #[test]
+    fn normalize_domain_strips_scheme_path_and_case() {
+        let got = normalize_domain("  HTTPS://Docs.Example.com/path ").unwrap();
+        assert_eq!(got, "docs.example.com");
+    }
+
+    /// Tiny deterministic generator so the equivalence property is checked
+    /// over many spellings without pulling in a property-testing crate.
+    fn case_variants(host: &str, seed: u64) -> Vec<String> {
+        let mut state = seed;
+        (0..16)
+            .map(|_| {
+                host.chars()
+                    .map(|c| {
+                        state = state
+                            .wrapping_mul(6_364_136_223_846_793_005)
+                            .wrapping_add(1);
+                        if state >> 63 == 1 {
+                            c.to_uppercase().collect::<String>()
+                        } else {
+                            c.to_lowercase().collect::<String>()
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn host_key_equal_for_all_equivalent_spellings() {
+        let cases: &[(&str, &[&str])] = &[
+            (
+                "example.com",
+                &[
+                    "Example.COM",
+                    "example.com.",
+                    " example.com ",
+                    "EXAMPLE.COM.",
+                ],
+            ),
+            (
+                "bücher.de",
+                &["xn--bcher-kva.de", "BÜCHER.de", "xn--bcher-kva.de."],
+            ),
+            ("例え.テスト", &["xn--r8jz45g.xn--zckzah", "例え。テスト"]),
+            ("127.0.0.1", &["127.0.0.1.", " 127.0.0.1"]),
+            ("::1", &["[::1]", "0:0:0:0:0:0:0:1", "[0::1]"]),
+            ("::ffff:10.0.0.1", &["::FFFF:10.0.0.1", "[::ffff:a00:1]"]),
+            ("2001:db8::1", &["2001:DB8::1", "[2001:0db8:0000::0001]"]),
+        ];
+
+        for (seed, (canonical, forms)) in cases.iter().enumerate() {
+            let expected = HostKey::from(*canonical);
+            for form in forms.iter().copied().map(String::from).chain(
+                case_variants(canonical, seed as u64 + 1)
+                    .into_iter()
+                    .chain(case_variants(&format!("{canonical}."), seed as u64 + 7)),
+            ) {
+                assert_eq!(HostKey::from(form.as_str()), expected, "form {form:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn host_key_distinguishes_different_hosts() {
+        assert_ne!(HostKey::from("example.com"), HostKey::from("example.org"));
+        assert_ne!(HostKey::from("a.example.com"), HostKey::from("example.com"));
+        assert_ne!(HostKey::from("127.0.0.1"), HostKey::from("127.0.0.2"));
+    }
+
+    #[test]
+    fn host_key_subdomain_match_requires_label_boundary() {
+        let domain = HostKey::from("Example.com.");
+        assert!(HostKey::from("API.example.com").is_within(&domain));
+        assert!(HostKey::from("example.com").is_within(&domain));
+        assert!(!HostKey::from("notexample.com").is_within(&domain));
+        assert!(HostKey::from("anything.test").is_within(&HostKey::from("*")));
+    }
+
+    #[test]
+    fn host_key_works_as_map_key() {
+        let mut seen = std::collections::HashMap::new();
+        seen.insert(HostKey::from("Docs.Example.com."), 1);
+        assert_eq!(seen.get(&HostKey::from("docs.example.com")), Some(&1));
+    }
+
+    #[test]
+    fn allowlist_matches_trailing_dot_and_idn_entries() {
+        let p = policy(&["Bücher.de", "example.com."]);
+        assert!(p.validate("https://xn--bcher-kva.de/").is_ok());
+        assert!(p.validate("https://api.EXAMPLE.com./v1").is_ok());
+        assert!(host_matches_allowlist(
+            "WWW.Example.Com.",
+            &["example.com".into()]
+        ));
+    }
+
+    #[test]
+    fn validated_url_carries_scheme_and_normalized_host() {
+        let got = policy(&["example.com"])
+            .validate("  https://Docs.Example.COM.:8443/path?q=1  ")
+            .unwrap();
+        assert_eq!(got.as_str(), "https://Docs.Example.COM.:8443/path?q=1");
+        assert_eq!(got.scheme(), "https");
+        assert_eq!(got.host(), "docs.example.com");
+    }
+
+    #[test]
+    fn rejects_empty_and_whitespace() {
+        let p = policy(&["example.com"]);
+        assert_eq!(p.validate("   "), Err(UrlValidationError::EmptyUrl));
+        assert_eq!(
+            p.validate("https://example.com/a b"),
+            Err(UrlValidationError::ContainsWhitespace)
+        );
+    }
+
+    #[test]
+    fn https_only_rejects_http() {
+        let p = UrlPolicy::new(SchemeConstraint::HttpsOnly, vec!["example.com".into()]);
+        assert_eq!(
+            p.validate("http://example.com"),
+            Err(UrlValidationError::DisallowedScheme {
+                required: SchemeConstraint::HttpsOnly
+            })
+        );
+        assert!(p.validate("https://example.com").is_ok());
+    }
+
+    #[test]
+    fn rejects_userinfo_ipv6_and_missing_host() {
+        let p = policy(&["*"]);
+        assert_eq!(
+            p.validate("https://user@example.com"),
+            Err(UrlValidationError::UserinfoNotAllowed)
+        );
+        assert_eq!(
+            p.validate("https://[::1]/"),
+            Err(UrlValidationError::Ipv6NotSupported)
+        );
+        assert_eq!(
+            p.validate("https:///path"),
+            Err(UrlValidationError::MissingHost)
+        );
+    }
+
+    #[test]
+    fn empty_allowlist_is_reported() {
+        assert_eq!(
+            policy(&[]).validate("https://example.com"),
+            Err(UrlValidationError::NoAllowlistConfigured)
+        );
+    }
+
+    #[test]
+    fn blocklist_precedes_private_host_allowance() {
+        let p = policy(&["*"])
+            .with_blocked_domains(vec!["192.168.1.5".into()])
+            .with_allowed_private_hosts(vec!["192.168.1.5".into()]);
+        assert_eq!(
+            p.validate("https://192.168.1.5"),
+            Err(UrlValidationError::BlockedDomain {
+                host: "192.168.1.5".into()
+            })
+        );
+    }
+
+    #[test]
+    fn allow_private_hosts_still_requires_allowlist() {
+        let p = policy(&["example.com"]).with_allow_private_hosts(true);
+        assert_eq!(
+            p.validate("http://10.0.0.1"),
+            Err(UrlValidationError::NotInAllowlist {
+                host: "10.0.0.1".into()
+            })
+        );
+    }
+
+    #[test]
+    fn resolved_non_global_ip_is_reported() {
+        let ips = [
+            "93.184.216.34".parse().unwrap(),
+            "10.0.0.1".parse().unwrap(),
+        ];
+        assert_eq!(
+            validate_resolved_ips_are_public("example.com", &ips),
+            Err(UrlValidationError::ResolvesToNonGlobal {
+                host: "example.com".into(),
+                ip: "10.0.0.1".parse().unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn alternate_ipv4_notations_decode_to_the_same_address() {
+        let loopback = NumericHost::Ipv4(Ipv4Addr::LOCALHOST);
+        for host in [
+            "2130706433",
+            "0x7f000001",
+            "0X7F000001",
+            "0177.0.0.1",
+            "0177.0000.0000.0001",
+            "127.000.000.001",
+            "0x7f.0.0.1",
+            "0x7f.0x0.0.01",
+            "127.1",
+            "127.0.1",
+            "0x7f.1",
+            "127.0.0.1.",
+        ] {
+            assert_eq!(classify_numeric_host(host), loopback, "{host}");
+        }
+    }
+
+    #[test]
+    fn numeric_hosts_that_do_not_decode_are_invalid() {
+        for host in [
+            "08.0.0.1",
+            "256.0.0.1",
+            "127.0.0.256",
+            "1.2.3.4.5",
+            "4294967296",
+            "0x1ffffffff",
+            "0xg.0.0.1",
+            "example.123",
+        ] {
+            assert_eq!(classify_numeric_host(host), NumericHost::Invalid, "{host}");
+        }
+    }
+
+    #[test]
+    fn hostnames_are_not_numeric() {
+        for host in [
+            "example.com",
+            "1.example.com",
+            "123abc",
+            "0xcafe.example",
+            "::1",
+        ] {
+            assert_eq!(
+                classify_numeric_host(host),
+                NumericHost::NotNumeric,
+                "{host}"
+            );
+        }
+    }
+
+    #[test]
+    fn alternate_notations_are_blocked_as_private() {
+        let p = policy(&["*"]);
+        for url in [
+            "http://0x7f000001",
+            "http://2130706433/admin",
+            "http://0177.0.0.1",
+            "http://127.000.000.001",
+            "http://0x7f.0.0.1:8080",
+            "http://0xa9.0xfe.0xa9.0xfe/latest/meta-data",
+        ] {
+            assert!(
+                matches!(
+                    p.validate(url),
+                    Err(UrlValidationError::PrivateOrLocalHost { .. })
+                ),
+                "{url}"
+            );
+        }
+    }
+
+    #[test]
+    fn alternate_notation_of_public_ip_is_canonicalized() {
+        assert_eq!(HostKey::from("0x08080808").as_str(), "8.8.8.8");
+        let p = policy(&["8.8.8.8"]);
+        let validated = p.validate("https://134744072/").unwrap();
+        assert_eq!(validated.host(), "8.8.8.8");
+    }
+
+    #[test]
+    fn hosts_the_client_parses_differently_are_rejected() {
+        let p = policy(&["allowed.com"]);
+        for (url, host) in [
+            ("http://127.0.0.1\\.allowed.com/", "127.0.0.1\\.allowed.com"),
+            ("http://127.0.0.%31/", "127.0.0.%31"),
+            ("http://0x7f.0.0.%31/", "0x7f.0.0.%31"),
+            ("http://%61llowed.com/", "%61llowed.com"),
+        ] {
+            assert_eq!(
+                p.validate(url),
+                Err(UrlValidationError::MalformedHost { host: host.into() }),
+                "{url}"
+            );
+        }
+        assert_eq!(
+            p.validate("http://api.allowed.com./x").unwrap().host(),
+            "api.allowed.com"
+        );
+    }
+
+    #[test]
+    fn undecodable_numeric_host_is_rejected() {
+        assert_eq!(
+            policy(&["*"]).validate("http://08.0.0.1/"),
+            Err(UrlValidationError::InvalidNumericHost {
+                host: "08.0.0.1".into()
+            })
+        );
+    }
+
+    #[test]
+    fn fragments_do_not_affect_validation() {
+        let p = policy(&["example.com"]);
+        let validated = p
+            .validate("https://example.com/cb#access_token=abc@evil.com/x")
+            .unwrap();
+        assert_eq!(validated.host(), "example.com");
+        assert_eq!(validated.fragment(), Some("access_token=abc@evil.com/x"));
+        assert_eq!(
+            p.validate("https://evil.com#example.com"),
+            Err(UrlValidationError::NotInAllowlist {
+                host: "evil.com".into()
+            })
+        );
+    }
+
+    #[test]
+    fn fragment_is_kept_in_url_but_stripped_for_keys_and_logs() {
+        let validated = policy(&["example.com"])
+            .validate("https://example.com/cb?x=1#access_token=SECRET&expires_in=3600")
+            .unwrap();
+        assert_eq!(
+            validated.as_str(),
+            "https://example.com/cb?x=1#access_token=SECRET&expires_in=3600"
+        );
+        assert_eq!(validated.without_fragment(), "https://example.com/cb?x=1");
+        assert_eq!(
+            validated.for_log(false),
+            "https://example.com/cb?x=1#[redacted]"
+        );
+        assert_eq!(validated.for_log(true), validated.as_str());
+
+        let empty = policy(&["example.com"])
+            .validate("https://example.com/page#")
+            .unwrap();
+        assert_eq!(empty.fragment(), None);
+        assert_eq!(empty.for_log(false), "https://example.com/page");
+    }
+
+    #[test]
+    fn redact_url_fragments_rewrites_embedded_urls() {
+        assert_eq!(
+            redact_url_fragments(
+                r#"url: https://app.example/cb#access_token=SECRET other: "http://x.test/#/route""#
+            ),
+            r#"url: https://app.example/cb#[redacted] other: "http://x.test/#[redacted]""#
+        );
+        assert_eq!(
+            redact_url_fragments("see https://example.com/a# and #channel"),
+            "see https://example.com/a and #channel"
+        );
+        assert!(matches!(
+            redact_url_fragments("no urls here"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn validate_offline_agrees_with_validate() {
+        let policy = policy(&["example.com"])
+            .with_blocked_domains(vec!["bad.example.com".into()])
+            .with_dns_check(true);
+        for url in [
+            "https://example.com/a",
+            "https://docs.example.com",
+            "https://bad.example.com",
+            "https://other.org",
+            "http://127.0.0.1",
+            "ftp://example.com",
+            "https://example.com/a b",
+        ] {
+            assert_eq!(
+                policy.validate_offline(url),
+                policy.validate(url),
+                "verdicts diverged for {url}"
+            );
+        }
+    }
+}
@@ -1,6 +1,16 @@
-use super::traits::{Tool, ToolResult};
+use super::header_order::{canonicalize_headers, casing_domains};
+use super::host_cooldown::HostCooldowns;
+use super::html_converter::{ConvertOptions, HtmlConverter, StreamingTextConverter};
+use super::pre_send::{InterceptDenied, PreSendInterceptor, intercept_request};
+use super::response_body::{EmptyBody, TruncatedTransfer, body_text};
+use super::retry_guidance::{RetryGuidance, SuggestedAction, rate_limited_error, read_only_error};
+use super::traits::{CostEstimate, Refusal, ShortCircuit, Tool, ToolResult};
+use super::url_validation::{
+    HostKey, SchemeConstraint, UrlPolicy, UrlValidationError, ValidatedUrl, host_key_matches,
+    redact_url_fragments, url_for_log,
+};
 use crate::config::schema::FirecrawlConfig;
-use crate::security::SecurityPolicy;
+use crate::security::{AutonomyLevel, SecurityPolicy};
 use async_trait::async_trait;
 use futures_util::StreamExt;
 use serde_json::json;
@@ -11,23 +21,42 @@ use std::time::Duration;
 /// Bodies shorter than this are treated as JS-only pages that need Firecrawl.
 const FIRECRAWL_MIN_BODY_LEN: usize = 100;
 
+/// Timeout for the Firecrawl fallback request, which renders the page remotely.
+const FIRECRAWL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Body chunks queued for the streaming converter before the download waits.
+const STREAMING_CHUNK_BACKLOG: usize = 4;
+
+/// Default number of redirect hops followed before giving up.
+const DEFAULT_MAX_REDIRECTS: usize = 5;
+
+/// User-Agent sent with every fetch.
+const USER_AGENT: &str = "ZeroClaw/0.1 (web_fetch)";
+
 /// Web fetch tool: fetches a web page and converts HTML to plain text for LLM consumption.
 ///
 /// Unlike `http_request` (an API client returning raw responses), this tool:
 /// - Only supports GET
-/// - Follows redirects (up to 10)
-/// - Converts HTML to clean plain text via `nanohtml2text`
+/// - Follows redirects manually (up to `max_redirects`), validating every hop
+/// - Converts HTML to clean plain text as it streams in, or via a custom [`HtmlConverter`]
 /// - Passes through text/plain, text/markdown, and application/json as-is
 /// - Sets a descriptive User-Agent
 /// - Falls back to Firecrawl API when standard fetch fails (if enabled)
 pub struct WebFetchTool {
     security: Arc<SecurityPolicy>,
-    allowed_domains: Vec<String>,
-    blocked_domains: Vec<String>,
-    allowed_private_hosts: Vec<String>,
+    policy: Arc<UrlPolicy>,
     max_response_size: usize,
     timeout_secs: u64,
+    max_redirects: usize,
     firecrawl: FirecrawlConfig,
+    /// Custom converter; `None` streams through [`StreamingTextConverter`].
+    converter: Option<Arc<dyn HtmlConverter>>,
+    interceptor: Option<Arc<dyn PreSendInterceptor>>,
+    cooldowns: Arc<HostCooldowns>,
+    /// Hosts sent `Title-Case` header names.
+    browser_like_casing: Vec<HostKey>,
+    /// Keep URL fragments in logs and error text (debug override).
+    log_fragments: bool,
 }
 
 impl WebFetchTool {
@@ -42,23 +71,287 @@ impl WebFetchTool {
     ) -> Self {
         Self {
             security,
-            allowed_domains: normalize_allowed_domains(allowed_domains),
-            blocked_domains: normalize_allowed_domains(blocked_domains),
-            allowed_private_hosts: normalize_allowed_domains(allowed_private_hosts),
+            policy: Arc::new(
+                UrlPolicy::new(SchemeConstraint::HttpOrHttps, allowed_domains)
+                    .with_blocked_domains(blocked_domains)
+                    .with_allowed_private_hosts(allowed_private_hosts)
+                    .with_dns_check(true),
+            ),
             max_response_size,
             timeout_secs,
+            max_redirects: DEFAULT_MAX_REDIRECTS,
             firecrawl,
+            converter: None,
+            interceptor: None,
+            cooldowns: Arc::new(HostCooldowns::new()),
+            browser_like_casing: Vec::new(),
+            log_fragments: false,
         }
     }
 
-    fn validate_url(&self, raw_url: &str) -> anyhow::Result<String> {
-        validate_target_url(
-            raw_url,
-            &self.allowed_domains,
-            &self.blocked_domains,
-            &self.allowed_private_hosts,
-            "web_fetch",
-        )
+    /// Override the maximum number of redirect hops followed per fetch.
+    pub fn with_max_redirects(mut self, max_redirects: usize) -> Self {
+        self.max_redirects = max_redirects;
+        self
+    }
+
+    /// Replace the built-in HTML-to-text conversion. Custom converters see
+    /// the whole (size-capped) document, so the body is buffered for them.
+    pub fn with_html_converter(mut self, converter: Arc<dyn HtmlConverter>) -> Self {
+        self.converter = Some(converter);
+        self
+    }
+
+    /// Let an embedder inspect, veto or tag each request, including every
+    /// redirect hop, just before it is sent.
+    pub fn with_pre_send_interceptor(mut self, interceptor: Arc<dyn PreSendInterceptor>) -> Self {
+        self.interceptor = Some(interceptor);
+        self
+    }
+
+    /// Send `Title-Case` header names to these domains and their subdomains.
+    pub fn with_browser_like_casing(mut self, domains: Vec<String>) -> Self {
+        self.browser_like_casing = casing_domains(&domains);
+        self
+    }
+
+    /// Share 429 cooldowns with other network tools.
+    pub fn with_host_cooldowns(mut self, cooldowns: Arc<HostCooldowns>) -> Self {
+        self.cooldowns = cooldowns;
+        self
+    }
+
+    /// Keep URL fragments in logs and errors instead of redacting them
+    /// (`[security.audit].log_fragments`).
+    pub fn with_log_fragments(mut self, log_fragments: bool) -> Self {
+        self.log_fragments = log_fragments;
+        self
+    }
+
+    fn validate_url(&self, raw_url: &str) -> Result<ValidatedUrl, UrlValidationError> {
+        self.policy.validate(raw_url)
+    }
+
+    /// Whether an operator is in the loop to extend `allowed_domains`.
+    fn approval_enabled(&self) -> bool {
+        self.security.autonomy == AutonomyLevel::Supervised
+    }
+
+    /// Retry guidance for a failed send, consistent with
+    /// [`url_error_message`](Self::url_error_message) for policy failures.
+    fn error_guidance(&self, err: &anyhow::Error) -> RetryGuidance {
+        RetryGuidance::for_error(err, self.approval_enabled())
+    }
+
+    /// [`describe_url_error`] with retry guidance. A host outside the
+    /// allowlist points at the operator in supervised mode.
+    fn url_error_message(&self, err: &UrlValidationError) -> String {
+        RetryGuidance::for_url_error(err, self.approval_enabled())
+            .append_to(&describe_url_error(err))
+    }
+
+    /// [`url_error_message`](Self::url_error_message) as a preflight refusal.
+    fn url_refusal(&self, err: &UrlValidationError) -> Refusal {
+        let guidance = RetryGuidance::for_url_error(err, self.approval_enabled());
+        Refusal {
+            needs_approval: guidance.suggested_action == SuggestedAction::AskOperatorForApproval,
+            ..self.url_error_message(err).into()
+        }
+    }
+
+    /// Checks shared by [`estimate`](Self::estimate) and `execute`: the `url`
+    /// argument, autonomy, the action budget, the offline URL policy and a
+    /// 429 cooldown on the host that outlasts the request timeout.
+    ///
+    /// The budget is only inspected here; `execute` records the action
+    /// afterwards. Errors carry retry guidance.
+    fn preflight(&self, args: &serde_json::Value) -> Result<ValidatedUrl, Refusal> {
+        let url = args
+            .get("url")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| RetryGuidance::DO_NOT_RETRY.append_to("Missing 'url' parameter"))?;
+        if !self.security.can_act() {
+            return Err(read_only_error().into());
+        }
+        if self.security.is_rate_limited() {
+            return Err(rate_limited_error().into());
+        }
+        let url = self
+            .policy
+            .validate_offline(url)
+            .map_err(|e| self.url_refusal(&e))?;
+        if let Err(cooling) = self.cooldowns.check(url.host_key(), self.request_timeout()) {
+            let short_circuit = ShortCircuit::Cooldown {
+                remaining: cooling.remaining,
+            };
+            let err = anyhow::Error::new(cooling);
+            return Err(Refusal {
+                short_circuit: Some(short_circuit),
+                ..self.error_guidance(&err).append_to(&err.to_string()).into()
+            });
+        }
+        Ok(url)
+    }
+
+    /// Estimate what `execute(args)` would cost without touching the network.
+    ///
+    /// Runs the same [`preflight`](Self::preflight) as execution, which skips
+    /// the DNS lookup. The byte bound covers the final response only; it is
+    /// unbounded when the Firecrawl fallback is enabled because that response
+    /// is not size-capped.
+    pub fn estimate(&self, args: &serde_json::Value) -> CostEstimate {
+        if let Err(refusal) = self.preflight(args) {
+            return refusal.into();
+        }
+
+        let fallback = usize::from(self.firecrawl.enabled);
+        CostEstimate {
+            request_count: 1,
+            max_request_count: 1 + self.max_redirects + fallback,
+            worst_case_bytes: (!self.firecrawl.enabled)
+                .then(|| self.max_response_size.saturating_add(1) as u64),
+            blocked: None,
+            may_prompt_approval: false,
+            short_circuit: None,
+        }
+    }
+
+    /// Build the fetch client. Automatic redirect following is disabled so
+    /// that `standard_fetch` can validate each hop itself.
+    fn build_client(&self) -> anyhow::Result<reqwest::Client> {
+        if self.timeout_secs == 0 {
+            tracing::warn!("web_fetch: timeout_secs is 0, using safe default of 30s");
+        }
+
+        self.build_client_with_casing(false)
+    }
+
+    /// [`build_client`](Self::build_client), optionally sending `Title-Case`
+    /// header names for hosts in `browser_like_casing_domains`.
+    fn build_client_with_casing(&self, title_case: bool) -> anyhow::Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(self.request_timeout())
+            .connect_timeout(Duration::from_secs(10))
+            .redirect(reqwest::redirect::Policy::none());
+        if title_case {
+            builder = builder.http1_title_case_headers();
+        }
+        let builder = crate::config::apply_runtime_proxy_to_builder(builder, "tool.web_fetch");
+        Ok(builder.build()?)
+    }
+
+    fn request_timeout(&self) -> Duration {
+        Duration::from_secs(if self.timeout_secs == 0 {
+            30
+        } else {
+            self.timeout_secs
+        })
+    }
+
+    /// Issue the GET for `url`, following redirects one hop at a time.
+    ///
+    /// Each `Location` is resolved against the current URL and run through the
+    /// full [`UrlPolicy`] before the next request is sent, so an allowlisted
+    /// site cannot bounce the fetch to a private or non-allowlisted host.
+    /// A hop to a host cooling down after a 429 waits or fails first.
+    ///
+    /// Returns the final response together with the URL it was served from.
+    async fn send_following_redirects(
+        &self,
+        client: &reqwest::Client,
+        url: &ValidatedUrl,
+    ) -> anyhow::Result<(reqwest::Response, ValidatedUrl)> {
+        let mut current = url.clone();
+        let mut visited = vec![current.without_fragment().to_string()];
+        let mut hop = 0;
+        let mut title_case_client = None;
+
+        loop {
+            let waited = self
+                .cooldowns
+                .wait_for(current.host_key(), self.request_timeout())
+                .await?;
+            // Time spent cooling down comes out of this hop's timeout.
+            let timeout = self.request_timeout().saturating_sub(waited);
+            let hop_client = if host_key_matches(current.host_key(), &self.browser_like_casing) {
+                if title_case_client.is_none() {
+                    title_case_client = Some(self.build_client_with_casing(true)?);
+                }
+                title_case_client.as_ref().unwrap_or(client)
+            } else {
+                client
+            };
+            let mut request = hop_client
+                .get(current.as_str())
+                .header(reqwest::header::USER_AGENT, USER_AGENT)
+                .build()
+                .map_err(|e| anyhow::anyhow!("HTTP request failed: {e}"))?;
+            canonicalize_headers(&mut request);
+            *request.timeout_mut() = Some(timeout);
+            if let Some(interceptor) = &self.interceptor {
+                intercept_request(
+                    interceptor.as_ref(),
+                    "web_fetch",
+                    &mut request,
+                    timeout,
+                    self.log_fragments,
+                )?;
+            }
+            let response = hop_client.execute(request).await.map_err(|e| {
+                let message = format!("HTTP request failed: {e}");
+                let message = if self.log_fragments {
+                    message
+                } else {
+                    redact_url_fragments(&message).into_owned()
+                };
+                anyhow::Error::new(e).context(message)
+            })?;
+            self.cooldowns.record_response(
+                current.host_key(),
+                response.status(),
+                response.headers(),
+            );
+
+            if !response.status().is_redirection() {
+                return Ok((response, current));
+            }
+            hop += 1;
+            let mut locations = response.headers().get_all(reqwest::header::LOCATION).iter();
+            let Some(location) = locations.next() else {
+                return Ok((response, current));
+            };
+            let from = current.for_log(self.log_fragments);
+            if locations.next().is_some() {
+                tracing::warn!(
+                    "web_fetch: redirect hop {hop} from {from} has several Location headers; following the first"
+                );
+            }
+            let location = location.as_bytes();
+
+            if hop > self.max_redirects {
+                anyhow::bail!(
+                    "Too many redirects (max {}): hop {hop} from {from} to {} was not followed",
+                    self.max_redirects,
+                    url_for_log(&String::from_utf8_lossy(location), self.log_fragments)
+                );
+            }
+
+            let next = resolve_redirect_hop(
+                &self.policy,
+                current.as_str(),
+                location,
+                hop,
+                self.log_fragments,
+            )?;
+            if visited.iter().any(|seen| seen == next.without_fragment()) {
+                anyhow::bail!(
+                    "Redirect loop detected at hop {hop}: {from} -> {} was already visited",
+                    next.for_log(self.log_fragments)
+                );
+            }
+            visited.push(next.without_fragment().to_string());
+            current = next;
+        }
     }
 
     fn truncate_response(&self, text: &str) -> String {
@@ -74,22 +367,93 @@ impl WebFetchTool {
         }
     }
 
-    async fn read_response_text_limited(
+    async fn read_response_bytes_limited(
         &self,
         response: reqwest::Response,
-    ) -> anyhow::Result<String> {
+    ) -> anyhow::Result<Vec<u8>> {
+        let declared = response.content_length();
         let mut bytes_stream = response.bytes_stream();
         let hard_cap = self.max_response_size.saturating_add(1);
         let mut bytes = Vec::new();
+        let mut received = 0u64;
 
         while let Some(chunk_result) = bytes_stream.next().await {
-            let chunk = chunk_result?;
+            let chunk = chunk_result.map_err(|e| truncated(declared, received, &e))?;
+            received += chunk.len() as u64;
             if append_chunk_with_cap(&mut bytes, &chunk, hard_cap) {
+                return Ok(bytes);
+            }
+        }
+
+        match TruncatedTransfer::check(declared, received) {
+            Some(short) => Err(short.into()),
+            None => Ok(bytes),
+        }
+    }
+
+    /// Convert an HTML body while it downloads, without buffering it.
+    ///
+    /// Reads at most `max_response_size` bytes, like the buffered path, and
+    /// stops early once the converted text reaches the output cap. A body of
+    /// only whitespace and byte order marks is described as empty. A transfer
+    /// that ends before its declared end fails with [`TruncatedTransfer`]
+    /// rather than passing for a shorter page.
+    ///
+    /// The converter is `!Send`, so it runs on a blocking thread and takes
+    /// chunks over a bounded channel; it drops the receiver once its output
+    /// is full, which ends the download.
+    async fn read_html_streaming(&self, response: reqwest::Response) -> anyhow::Result<String> {
+        let status = response.status().as_u16();
+        let declared = response.content_length();
+        let max_output_chars = self.max_response_size;
+        let (chunk_tx, mut chunk_rx) =
+            tokio::sync::mpsc::channel::<hyper::body::Bytes>(STREAMING_CHUNK_BACKLOG);
+        let conversion = tokio::task::spawn_blocking(move || {
+            let mut converter = StreamingTextConverter::new(max_output_chars);
+            while let Some(chunk) = chunk_rx.blocking_recv() {
+                converter.push(&chunk);
+                if converter.is_full() {
+                    break;
+                }
+            }
+            converter.finish()
+        });
+        let mut bytes_stream = response.bytes_stream();
+        let hard_cap = self.max_response_size.saturating_add(1);
+        let mut remaining = hard_cap;
+        let mut blank = true;
+        let mut received = 0u64;
+        let mut stopped_early = false;
+
+        while let Some(chunk_result) = bytes_stream.next().await {
+            let chunk = chunk_result.map_err(|e| truncated(declared, received, &e))?;
+            received += chunk.len() as u64;
+            let take = chunk.len().min(remaining);
+            blank = blank && EmptyBody::classify(status, &chunk[..take]).is_some();
+            remaining -= take;
+            if chunk_tx.send(chunk.slice(..take)).await.is_err() || remaining == 0 {
+                stopped_early = true;
                 break;
             }
         }
+        if let Some(short) = TruncatedTransfer::check(declared, received).filter(|_| !stopped_early)
+        {
+            return Err(short.into());
+        }
 
-        Ok(String::from_utf8_lossy(&bytes).into_owned())
+        if blank {
+            let empty = match hard_cap - remaining {
+                0 => EmptyBody::NoContent,
+                bytes => EmptyBody::Blank { bytes },
+            };
+            return Ok(empty.describe(status));
+        }
+        drop(chunk_tx);
+        let text = conversion.await?;
+        Ok(match text.strip_prefix('\u{feff}') {
+            Some(rest) => rest.to_string(),
+            None => text,
+        })
     }
 
     /// Whether the standard fetch result should trigger a Firecrawl fallback.
@@ -120,7 +484,7 @@ impl WebFetchTool {
         let endpoint = format!("{}/scrape", self.firecrawl.api_url.trim_end_matches('/'));
 
         let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(60))
+            .timeout(FIRECRAWL_TIMEOUT)
             .build()
             .map_err(|e| anyhow::anyhow!("Failed to build Firecrawl HTTP client: {e}"))?;
 
@@ -129,12 +493,28 @@ impl WebFetchTool {
             "formats": ["markdown"]
         });
 
-        let response = client
+        let mut request = client
             .post(&endpoint)
             .header("Authorization", format!("Bearer {api_key}"))
             .header("Content-Type", "application/json")
             .json(&body)
-            .send()
+            .build()
+            .map_err(|e| anyhow::anyhow!("Firecrawl request failed: {e}"))?;
+        canonicalize_headers(&mut request);
+        // The POST carries the target URL, so it needs the same approval as
+        // a direct fetch would.
+        if let Some(interceptor) = &self.interceptor {
+            intercept_request(
+                interceptor.as_ref(),
+                "web_fetch",
+                &mut request,
+                FIRECRAWL_TIMEOUT,
+                self.log_fragments,
+            )?;
+        }
+
+        let response = client
+            .execute(request)
             .await
             .map_err(|e| anyhow::anyhow!("Firecrawl request failed: {e}"))?;
 
@@ -181,28 +561,64 @@ impl WebFetchTool {
     }
 
     /// Perform the standard HTTP GET fetch and convert to text.
-    async fn standard_fetch(&self, client: &reqwest::Client, url: &str) -> ToolResult {
-        let response = match client.get(url).send().await {
+    ///
+    /// A pre-send interceptor veto on any hop is returned as
+    /// [`InterceptDenied`] rather than as a failed [`ToolResult`], so that the
+    /// caller cannot mistake it for a page worth fetching another way.
+    async fn standard_fetch(
+        &self,
+        client: &reqwest::Client,
+        url: &ValidatedUrl,
+    ) -> Result<ToolResult, InterceptDenied> {
+        let (response, final_url) = match self.send_following_redirects(client, url).await {
             Ok(r) => r,
             Err(e) => {
-                return ToolResult {
+                if let Some(denied) = e.downcast_ref::<InterceptDenied>() {
+                    return Err(denied.clone());
+                }
+                return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(format!("HTTP request failed: {e}")),
-                };
+                    error: Some(self.error_guidance(&e).append_to(&e.to_string())),
+                });
             }
         };
+        Ok(self.render_response(response, final_url).await)
+    }
 
+    /// Turn the final response of a fetch into the tool result.
+    async fn render_response(
+        &self,
+        response: reqwest::Response,
+        final_url: ValidatedUrl,
+    ) -> ToolResult {
         let status = response.status();
         if !status.is_success() {
+            let message = format!(
+                "HTTP {} {}",
+                status.as_u16(),
+                status.canonical_reason().unwrap_or("Unknown")
+            );
+            let output = if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                self.cooldowns.describe_active()
+            } else {
+                String::new()
+            };
             return ToolResult {
                 success: false,
-                output: String::new(),
-                error: Some(format!(
-                    "HTTP {} {}",
-                    status.as_u16(),
-                    status.canonical_reason().unwrap_or("Unknown")
-                )),
+                output,
+                error: Some(
+                    RetryGuidance::for_status(status, response.headers()).append_to(&message),
+                ),
+            };
+        }
+
+        // A 204 has nothing to convert, whatever its content type claims.
+        if let Some(empty) = EmptyBody::from_status(status.as_u16()) {
+            return ToolResult {
+                success: true,
+                output: empty.describe(status.as_u16()),
+                error: None,
             };
         }
 
@@ -225,28 +641,81 @@ impl WebFetchTool {
             return ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some(format!(
+                error: Some(RetryGuidance::USE_DIFFERENT_URL.append_to(&format!(
                     "Unsupported content type: {content_type}. \
-                     web_fetch supports text/html, text/plain, text/markdown, and application/json."
-                )),
+                     web_fetch supports text/html, text/plain, text/markdown, and application/json"
+                ))),
             };
         };
 
-        let body = match self.read_response_text_limited(response).await {
-            Ok(t) => t,
+        if body_mode == "html" && self.converter.is_none() {
+            return match self.read_html_streaming(response).await {
+                Ok(text) => ToolResult {
+                    success: true,
+                    output: self.truncate_response(&text),
+                    error: None,
+                },
+                Err(e) => ToolResult {
+                    success: false,
+                    output: truncation_marker(&e),
+                    error: Some(
+                        self.error_guidance(&e)
+                            .append_to(&format!("Failed to read response body: {e}")),
+                    ),
+                },
+            };
+        }
+
+        let bytes = match self.read_response_bytes_limited(response).await {
+            Ok(b) => b,
             Err(e) => {
                 return ToolResult {
                     success: false,
-                    output: String::new(),
-                    error: Some(format!("Failed to read response body: {e}")),
+                    output: truncation_marker(&e),
+                    error: Some(
+                        self.error_guidance(&e)
+                            .append_to(&format!("Failed to read response body: {e}")),
+                    ),
                 };
             }
         };
+        if let Some(empty) = EmptyBody::classify(status.as_u16(), &bytes) {
+            return ToolResult {
+                success: true,
+                output: empty.describe(status.as_u16()),
+                error: None,
+            };
+        }
+        let body = body_text(&bytes);
 
-        let text = if body_mode == "html" {
-            nanohtml2text::html2text(&body)
-        } else {
-            body
+        let text = match (&self.converter, body_mode) {
+            (Some(converter), "html") => {
+                let opts = ConvertOptions {
+                    max_output_chars: self.max_response_size,
+                };
+                match converter.convert(&body, &final_url, &opts) {
+                    Ok(doc) => {
+                        for warning in &doc.warnings {
+                            tracing::warn!(
+                                "web_fetch: conversion of {}: {warning}",
+                                final_url.for_log(self.log_fragments)
+                            );
+                        }
+                        doc.text
+                    }
+                    Err(e) => {
+                        return ToolResult {
+                            success: false,
+                            output: String::new(),
+                            error: Some(RetryGuidance::DO_NOT_RETRY.append_to(&format!(
+                                "Failed to convert HTML from {}: {e}",
+                                final_url.for_log(self.log_fragments)
+                            ))),
+                        };
+                    }
+                }
+            }
+            _ => body,
         };
 
         let output = self.truncate_response(&text);
@@ -288,96 +757,76 @@ impl Tool for WebFetchTool {
     }
 
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
-        let url = args
-            .get("url")
-            .and_then(|v| v.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Missing 'url' parameter"))?;
-
-        if !self.security.can_act() {
-            return Ok(ToolResult {
-                success: false,
-                output: String::new(),
-                error: Some("Action blocked: autonomy is read-only".into()),
-            });
-        }
+        let url = match self.preflight(&args) {
+            Ok(url) => url,
+            Err(refusal) => {
+                let output = if refusal.short_circuit.is_some() {
+                    self.cooldowns.describe_active()
+                } else {
+                    String::new()
+                };
+                return Ok(ToolResult {
+                    success: false,
+                    output,
+                    error: Some(refusal.message),
+                });
+            }
+        };
 
         if !self.security.record_action() {
             return Ok(ToolResult {
                 success: false,
                 output: String::new(),
-                error: Some("Action blocked: rate limit exceeded".into()),
+                error: Some(rate_limited_error()),
             });
         }
 
-        let url = match self.validate_url(url) {
+        let url = match self.validate_url(url.as_str()) {
             Ok(v) => v,
             Err(e) => {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(e.to_string()),
+                    error: Some(self.url_error_message(&e)),
                 });
             }
         };
 
-        // Build client: follow redirects, set timeout, set User-Agent
-        let timeout_secs = if self.timeout_secs == 0 {
-            tracing::warn!("web_fetch: timeout_secs is 0, using safe default of 30s");
-            30
-        } else {
-            self.timeout_secs
-        };
-
-        let allowed_domains = self.allowed_domains.clone();
-        let blocked_domains = self.blocked_domains.clone();
-        let allowed_private_hosts = self.allowed_private_hosts.clone();
-        let redirect_policy = reqwest::redirect::Policy::custom(move |attempt| {
-            if attempt.previous().len() >= 10 {
-                return attempt.error(std::io::Error::other("Too many redirects (max 10)"));
-            }
-
-            if let Err(err) = validate_target_url(
-                attempt.url().as_str(),
-                &allowed_domains,
-                &blocked_domains,
-                &allowed_private_hosts,
-                "web_fetch",
-            ) {
-                return attempt.error(std::io::Error::new(
-                    std::io::ErrorKind::PermissionDenied,
-                    format!("Blocked redirect target: {err}"),
-                ));
-            }
-
-            attempt.follow()
-        });
-
-        let builder = reqwest::Client::builder()
-            .timeout(Duration::from_secs(timeout_secs))
-            .connect_timeout(Duration::from_secs(10))
-            .redirect(redirect_policy)
-            .user_agent("ZeroClaw/0.1 (web_fetch)");
-        let builder = crate::config::apply_runtime_proxy_to_builder(builder, "tool.web_fetch");
-        let client = match builder.build() {
+        let client = match self.build_client() {
             Ok(c) => c,
             Err(e) => {
                 return Ok(ToolResult {
                     success: false,
                     output: String::new(),
-                    error: Some(format!("Failed to build HTTP client: {e}")),
+                    error: Some(
+                        RetryGuidance::DO_NOT_RETRY
+                            .append_to(&format!("Failed to build HTTP client: {e}")),
+                    ),
                 });
             }
         };
 
-        let standard_result = self.standard_fetch(&client, &url).await;
+        let standard_result = match self.standard_fetch(&client, &url).await {
+            Ok(result) => result,
+            // A veto covers the target itself, so Firecrawl must not fetch it
+            // on the model's behalf either.
+            Err(denied) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(RetryGuidance::DO_NOT_RETRY.append_to(&denied.to_string())),
+                });
+            }
+        };
 
         // If standard fetch succeeded well enough, return it directly.
         // Otherwise, try Firecrawl fallback if enabled.
         if self.should_fallback_to_firecrawl(&standard_result) {
             tracing::info!(
-                "web_fetch: standard fetch insufficient for {url}, attempting Firecrawl fallback"
+                "web_fetch: standard fetch insufficient for {}, attempting Firecrawl fallback",
+                url.for_log(self.log_fragments)
             );
-            match Box::pin(self.fetch_via_firecrawl(&url)).await {
+            match Box::pin(self.fetch_via_firecrawl(url.as_str())).await {
                 Ok(firecrawl_result) if firecrawl_result.success => {
                     return Ok(firecrawl_result);
                 }
@@ -398,68 +847,107 @@ impl Tool for WebFetchTool {
     }
 }
 
-// ── Helper functions (independent from http_request.rs per DRY rule-of-three) ──
-
-fn validate_target_url(
-    raw_url: &str,
-    allowed_domains: &[String],
-    blocked_domains: &[String],
-    allowed_private_hosts: &[String],
-    tool_name: &str,
-) -> anyhow::Result<String> {
-    let url = raw_url.trim();
+// ── Helper functions ─────────────────────────────────────────────
 
-    if url.is_empty() {
-        anyhow::bail!("URL cannot be empty");
-    }
-
-    if url.chars().any(char::is_whitespace) {
-        anyhow::bail!("URL cannot contain whitespace");
-    }
-
-    if !url.starts_with("http://") && !url.starts_with("https://") {
-        anyhow::bail!("Only http:// and https:// URLs are allowed");
-    }
-
-    if allowed_domains.is_empty() {
-        anyhow::bail!(
-            "{tool_name} tool is enabled but no allowed_domains are configured. \
-             Add [{tool_name}].allowed_domains in config.toml"
-        );
+/// Map a validation failure to the operator-facing message for this tool.
+fn describe_url_error(err: &UrlValidationError) -> String {
+    match err {
+        UrlValidationError::NoAllowlistConfigured => {
+            "web_fetch tool is enabled but no allowed_domains are configured. \
+             Add [web_fetch].allowed_domains in config.toml"
+                .into()
+        }
+        UrlValidationError::NotInAllowlist { host } => format!(
+            "Host '{host}' is not in web_fetch.allowed_domains. \
+             Add this domain to [web_fetch].allowed_domains in config.toml to allow it"
+        ),
+        UrlValidationError::BlockedDomain { host } => {
+            format!("Host '{host}' is in web_fetch.blocked_domains")
+        }
+        UrlValidationError::PrivateOrLocalHost { host } => format!(
+            "Blocked local/private host: {host}. \
+             To allow this host, add it to web_fetch.allowed_private_hosts in config.toml"
+        ),
+        UrlValidationError::Ipv6NotSupported => "IPv6 hosts are not supported in web_fetch".into(),
+        other => other.to_string(),
     }
+}
 
-    let host = extract_host(url)?;
-
-    // blocked_domains always takes precedence
-    if host_matches_allowlist(&host, blocked_domains) {
-        anyhow::bail!("Host '{host}' is in {tool_name}.blocked_domains");
-    }
+/// Record a body stream failure as a [`TruncatedTransfer`].
+fn truncated(declared: Option<u64>, received: u64, err: &reqwest::Error) -> anyhow::Error {
+    tracing::warn!("web_fetch: response body ended early after {received} bytes: {err}");
+    TruncatedTransfer { declared, received }.into()
+}
 
-    let private_host_allowed =
-        is_private_or_local_host(&host) && host_matches_allowlist(&host, allowed_private_hosts);
+/// Output for a failed body read: the [`TruncatedTransfer`] marker in place
+/// of the partial body, as in `http_request`, or nothing for other failures.
+fn truncation_marker(err: &anyhow::Error) -> String {
+    err.downcast_ref::<TruncatedTransfer>()
+        .map(|short| short.marker())
+        .unwrap_or_default()
+}
 
-    if is_private_or_local_host(&host) && !private_host_allowed {
-        anyhow::bail!(
-            "Blocked local/private host: {host}. \
-             To allow this host, add it to {tool_name}.allowed_private_hosts in config.toml"
-        );
-    }
+/// Resolve a raw redirect `Location` against `current` and validate the target.
+///
+/// The value is first passed through [`normalize_location`], then resolved as
+/// an RFC 3986 reference, so relative paths without a leading slash and
+/// `//host` forms work like they do in browsers. Errors name the hop number
+/// and both ends of the hop so the caller can tell which link in the chain
+/// was rejected and why; unparseable values are quoted as received. URLs in
+/// errors have their fragments redacted unless `log_fragments` is set.
+fn resolve_redirect_hop(
+    policy: &UrlPolicy,
+    current: &str,
+    location: &[u8],
+    hop: usize,
+    log_fragments: bool,
+) -> anyhow::Result<ValidatedUrl> {
+    let from = url_for_log(current, log_fragments);
+    let base = reqwest::Url::parse(current)
+        .map_err(|e| anyhow::anyhow!("Redirect hop {hop}: invalid current URL {from}: {e}"))?;
+    let normalized = normalize_location(location);
+    if normalized.is_empty() {
+        anyhow::bail!("Redirect hop {hop} from {from}: Location header is empty");
+    }
+    let next = base.join(&normalized).map_err(|e| {
+        anyhow::anyhow!(
+            "Redirect hop {hop} from {from}: unparseable Location {:?}: {e}",
+            url_for_log(&String::from_utf8_lossy(location), log_fragments)
+        )
+    })?;
 
-    if private_host_allowed {
-        tracing::warn!(
-            "{tool_name}: allowing private/local host '{host}' via allowed_private_hosts"
+    policy.validate(next.as_str()).map_err(|err| {
+        let message = format!(
+            "Blocked redirect at hop {hop} ({from} -> {}): {}",
+            url_for_log(next.as_str(), log_fragments),
+            describe_url_error(&err)
         );
-    }
-
-    if !private_host_allowed && !host_matches_allowlist(&host, allowed_domains) {
-        anyhow::bail!("Host '{host}' is not in {tool_name}.allowed_domains");
-    }
+        anyhow::Error::new(err).context(message)
+    })
+}
 
-    if !private_host_allowed {
-        validate_resolved_host_is_public(&host)?;
+/// Make a raw `Location` value safe to parse, tolerating common server mistakes.
+///
+/// Surrounding whitespace is trimmed. Bytes that cannot appear in a URL
+/// (spaces, controls, raw UTF-8 and the ASCII characters RFC 3986 excludes)
+/// are percent-encoded; existing `%XX` escapes and reserved characters are
+/// kept, so a well-formed value passes through unchanged.
+fn normalize_location(raw: &[u8]) -> String {
+    use std::fmt::Write as _;
+
+    let mut out = String::with_capacity(raw.len());
+    for &byte in raw.trim_ascii() {
+        match byte {
+            b'"' | b'<' | b'>' | b'^' | b'`' | b'{' | b'|' | b'}' => {
+                let _ = write!(out, "%{byte:02X}");
+            }
+            b'!'..=b'~' => out.push(char::from(byte)),
+            _ => {
+                let _ = write!(out, "%{byte:02X}");
+            }
+        }
     }
-
-    Ok(url.to_string())
+    out
 }
 
 fn append_chunk_with_cap(buffer: &mut Vec<u8>, chunk: &[u8], hard_cap: usize) -> bool {
@@ -477,235 +965,68 @@ fn append_chunk_with_cap(buffer: &mut Vec<u8>, chunk: &[u8], hard_cap: usize) ->
     buffer.len() >= hard_cap
 }
 
-fn normalize_allowed_domains(domains: Vec<String>) -> Vec<String> {
-    let mut normalized = domains
-        .into_iter()
-        .filter_map(|d| normalize_domain(&d))
-        .collect::<Vec<_>>();
-    normalized.sort_unstable();
-    normalized.dedup();
-    normalized
-}
-
-fn normalize_domain(raw: &str) -> Option<String> {
-    let mut d = raw.trim().to_lowercase();
-    if d.is_empty() {
-        return None;
-    }
-
-    if let Some(stripped) = d.strip_prefix("https://") {
-        d = stripped.to_string();
-    } else if let Some(stripped) = d.strip_prefix("http://") {
-        d = stripped.to_string();
-    }
-
-    if let Some((host, _)) = d.split_once('/') {
-        d = host.to_string();
-    }
-
-    d = d.trim_start_matches('.').trim_end_matches('.').to_string();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::FirecrawlConfig;
+    use crate::security::{AutonomyLevel, SecurityPolicy};
+    use crate::tools::url_validation::{
+        is_private_or_local_host, normalize_allowed_domains, normalize_domain,
+        validate_resolved_ips_are_public,
+    };
 
-    if let Some((host, _)) = d.split_once(':') {
-        d = host.to_string();
+    fn test_tool(allowed_domains: Vec<&str>) -> WebFetchTool {
+        test_tool_with_blocklist(allowed_domains, vec![])
     }
 
-    if d.is_empty() || d.chars().any(char::is_whitespace) {
-        return None;
+    /// Validate a wiremock URL for tests that call `standard_fetch` directly.
+    fn validated(url: &str) -> ValidatedUrl {
+        UrlPolicy::new(SchemeConstraint::HttpOrHttps, vec!["*".into()])
+            .with_allowed_private_hosts(vec!["127.0.0.1".into()])
+            .validate(url)
+            .unwrap()
     }
 
-    Some(d)
-}
-
-fn extract_host(url: &str) -> anyhow::Result<String> {
-    let rest = url
-        .strip_prefix("http://")
-        .or_else(|| url.strip_prefix("https://"))
-        .ok_or_else(|| anyhow::anyhow!("Only http:// and https:// URLs are allowed"))?;
-
-    let authority = rest
-        .split(['/', '?', '#'])
-        .next()
-        .ok_or_else(|| anyhow::anyhow!("Invalid URL"))?;
-
-    if authority.is_empty() {
-        anyhow::bail!("URL must include a host");
+    fn test_tool_with_blocklist(
+        allowed_domains: Vec<&str>,
+        blocked_domains: Vec<&str>,
+    ) -> WebFetchTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            ..SecurityPolicy::default()
+        });
+        WebFetchTool::new(
+            security,
+            allowed_domains.into_iter().map(String::from).collect(),
+            blocked_domains.into_iter().map(String::from).collect(),
+            500_000,
+            30,
+            FirecrawlConfig::default(),
+            vec![],
+        )
     }
 
-    if authority.contains('@') {
-        anyhow::bail!("URL userinfo is not allowed");
-    }
-
-    if authority.starts_with('[') {
-        anyhow::bail!("IPv6 hosts are not supported in web_fetch");
-    }
-
-    let host = authority
-        .split(':')
-        .next()
-        .unwrap_or_default()
-        .trim()
-        .trim_end_matches('.')
-        .to_lowercase();
-
-    if host.is_empty() {
-        anyhow::bail!("URL must include a valid host");
-    }
-
-    Ok(host)
-}
-
-fn host_matches_allowlist(host: &str, allowed_domains: &[String]) -> bool {
-    if allowed_domains.iter().any(|domain| domain == "*") {
-        return true;
-    }
-
-    allowed_domains.iter().any(|domain| {
-        host == domain
-            || host
-                .strip_suffix(domain)
-                .is_some_and(|prefix| prefix.ends_with('.'))
-    })
-}
-
-fn is_private_or_local_host(host: &str) -> bool {
-    let bare = host
-        .strip_prefix('[')
-        .and_then(|h| h.strip_suffix(']'))
-        .unwrap_or(host);
-
-    let has_local_tld = bare
-        .rsplit('.')
-        .next()
-        .is_some_and(|label| label == "local");
-
-    if bare == "localhost" || bare.ends_with(".localhost") || has_local_tld {
-        return true;
-    }
-
-    if let Ok(ip) = bare.parse::<std::net::IpAddr>() {
-        return match ip {
-            std::net::IpAddr::V4(v4) => is_non_global_v4(v4),
-            std::net::IpAddr::V6(v6) => is_non_global_v6(v6),
-        };
-    }
-
-    false
-}
-
-#[cfg(not(test))]
-fn validate_resolved_host_is_public(host: &str) -> anyhow::Result<()> {
-    use std::net::ToSocketAddrs;
-
-    let ips = (host, 0)
-        .to_socket_addrs()
-        .map_err(|e| anyhow::anyhow!("Failed to resolve host '{host}': {e}"))?
-        .map(|addr| addr.ip())
-        .collect::<Vec<_>>();
-
-    validate_resolved_ips_are_public(host, &ips)
-}
-
-#[cfg(test)]
-fn validate_resolved_host_is_public(_host: &str) -> anyhow::Result<()> {
-    // DNS checks are covered by validate_resolved_ips_are_public unit tests.
-    Ok(())
-}
-
-fn validate_resolved_ips_are_public(host: &str, ips: &[std::net::IpAddr]) -> anyhow::Result<()> {
-    if ips.is_empty() {
-        anyhow::bail!("Failed to resolve host '{host}'");
-    }
-
-    for ip in ips {
-        let non_global = match ip {
-            std::net::IpAddr::V4(v4) => is_non_global_v4(*v4),
-            std::net::IpAddr::V6(v6) => is_non_global_v6(*v6),
-        };
-        if non_global {
-            anyhow::bail!("Blocked host '{host}' resolved to non-global address {ip}");
-        }
-    }
-
-    Ok(())
-}
-
-fn is_non_global_v4(v4: std::net::Ipv4Addr) -> bool {
-    let [a, b, c, _] = v4.octets();
-    v4.is_loopback()
-        || v4.is_private()
-        || v4.is_link_local()
-        || v4.is_unspecified()
-        || v4.is_broadcast()
-        || v4.is_multicast()
-        || (a == 100 && (64..=127).contains(&b))
-        || a >= 240
-        || (a == 192 && b == 0 && (c == 0 || c == 2))
-        || (a == 198 && b == 51)
-        || (a == 203 && b == 0)
-        || (a == 198 && (18..=19).contains(&b))
-}
-
-fn is_non_global_v6(v6: std::net::Ipv6Addr) -> bool {
-    let segs = v6.segments();
-    v6.is_loopback()
-        || v6.is_unspecified()
-        || v6.is_multicast()
-        || (segs[0] & 0xfe00) == 0xfc00
-        || (segs[0] & 0xffc0) == 0xfe80
-        || (segs[0] == 0x2001 && segs[1] == 0x0db8)
-        || v6.to_ipv4_mapped().is_some_and(is_non_global_v4)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::config::schema::FirecrawlConfig;
-    use crate::security::{AutonomyLevel, SecurityPolicy};
-
-    fn test_tool(allowed_domains: Vec<&str>) -> WebFetchTool {
-        test_tool_with_blocklist(allowed_domains, vec![])
-    }
-
-    fn test_tool_with_blocklist(
-        allowed_domains: Vec<&str>,
-        blocked_domains: Vec<&str>,
-    ) -> WebFetchTool {
-        let security = Arc::new(SecurityPolicy {
-            autonomy: AutonomyLevel::Supervised,
-            ..SecurityPolicy::default()
-        });
-        WebFetchTool::new(
-            security,
-            allowed_domains.into_iter().map(String::from).collect(),
-            blocked_domains.into_iter().map(String::from).collect(),
-            500_000,
-            30,
-            FirecrawlConfig::default(),
-            vec![],
-        )
-    }
-
-    fn test_tool_with_private_hosts(
-        allowed_domains: Vec<&str>,
-        blocked_domains: Vec<&str>,
-        allowed_private_hosts: Vec<&str>,
-    ) -> WebFetchTool {
-        let security = Arc::new(SecurityPolicy {
-            autonomy: AutonomyLevel::Supervised,
-            ..SecurityPolicy::default()
-        });
-        WebFetchTool::new(
-            security,
-            allowed_domains.into_iter().map(String::from).collect(),
-            blocked_domains.into_iter().map(String::from).collect(),
-            500_000,
-            30,
-            FirecrawlConfig::default(),
-            allowed_private_hosts
-                .into_iter()
-                .map(String::from)
-                .collect(),
-        )
+    fn test_tool_with_private_hosts(
+        allowed_domains: Vec<&str>,
+        blocked_domains: Vec<&str>,
+        allowed_private_hosts: Vec<&str>,
+    ) -> WebFetchTool {
+        let security = Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Supervised,
+            ..SecurityPolicy::default()
+        });
+        WebFetchTool::new(
+            security,
+            allowed_domains.into_iter().map(String::from).collect(),
+            blocked_domains.into_iter().map(String::from).collect(),
+            500_000,
+            30,
+            FirecrawlConfig::default(),
+            allowed_private_hosts
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        )
     }
 
     fn test_tool_with_firecrawl(firecrawl: FirecrawlConfig) -> WebFetchTool {
@@ -746,7 +1067,7 @@ mod tests {
     #[test]
     fn html_to_text_conversion() {
         let html = "<html><body><h1>Title</h1><p>Hello <b>world</b></p></body></html>";
-        let text = nanohtml2text::html2text(html);
+        let text = crate::tools::html_converter::html_to_text(html, 10_000);
         assert!(text.contains("Title"));
         assert!(text.contains("Hello"));
         assert!(text.contains("world"));
@@ -760,7 +1081,7 @@ mod tests {
     fn validate_accepts_exact_domain() {
         let tool = test_tool(vec!["example.com"]);
         let got = tool.validate_url("https://example.com/page").unwrap();
-        assert_eq!(got, "https://example.com/page");
+        assert_eq!(got.as_str(), "https://example.com/page");
     }
 
     #[test]
@@ -778,35 +1099,34 @@ mod tests {
     #[test]
     fn validate_rejects_empty_url() {
         let tool = test_tool(vec!["example.com"]);
-        let err = tool.validate_url("").unwrap_err().to_string();
-        assert!(err.contains("empty"));
+        let err = tool.validate_url("").unwrap_err();
+        assert!(matches!(err, UrlValidationError::EmptyUrl));
     }
 
     #[test]
     fn validate_rejects_missing_url() {
         let tool = test_tool(vec!["example.com"]);
-        let err = tool.validate_url("  ").unwrap_err().to_string();
-        assert!(err.contains("empty"));
+        let err = tool.validate_url("  ").unwrap_err();
+        assert!(matches!(err, UrlValidationError::EmptyUrl));
     }
 
     #[test]
     fn validate_rejects_ftp_scheme() {
         let tool = test_tool(vec!["example.com"]);
-        let err = tool
-            .validate_url("ftp://example.com")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("http://") || err.contains("https://"));
+        let err = tool.validate_url("ftp://example.com").unwrap_err();
+        assert!(matches!(err, UrlValidationError::DisallowedScheme { .. }));
     }
 
     #[test]
     fn validate_rejects_allowlist_miss() {
         let tool = test_tool(vec!["example.com"]);
-        let err = tool
-            .validate_url("https://google.com")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("allowed_domains"));
+        let err = tool.validate_url("https://google.com").unwrap_err();
+        assert_eq!(
+            err,
+            UrlValidationError::NotInAllowlist {
+                host: "google.com".into()
+            }
+        );
     }
 
     #[test]
@@ -821,11 +1141,8 @@ mod tests {
             FirecrawlConfig::default(),
             vec![],
         );
-        let err = tool
-            .validate_url("https://example.com")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("allowed_domains"));
+        let err = tool.validate_url("https://example.com").unwrap_err();
+        assert_eq!(err, UrlValidationError::NoAllowlistConfigured);
     }
 
     // ── SSRF protection ──────────────────────────────────────────
@@ -833,21 +1150,15 @@ mod tests {
     #[test]
     fn ssrf_blocks_localhost() {
         let tool = test_tool(vec!["localhost"]);
-        let err = tool
-            .validate_url("https://localhost:8080")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("local/private"));
+        let err = tool.validate_url("https://localhost:8080").unwrap_err();
+        assert!(matches!(err, UrlValidationError::PrivateOrLocalHost { .. }));
     }
 
     #[test]
     fn ssrf_blocks_private_ipv4() {
         let tool = test_tool(vec!["192.168.1.5"]);
-        let err = tool
-            .validate_url("https://192.168.1.5")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("local/private"));
+        let err = tool.validate_url("https://192.168.1.5").unwrap_err();
+        assert!(matches!(err, UrlValidationError::PrivateOrLocalHost { .. }));
     }
 
     #[test]
@@ -866,59 +1177,37 @@ mod tests {
     #[test]
     fn ssrf_wildcard_still_blocks_private() {
         let tool = test_tool(vec!["*"]);
-        let err = tool
-            .validate_url("https://localhost:8080")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("local/private"));
+        let err = tool.validate_url("https://localhost:8080").unwrap_err();
+        assert!(matches!(err, UrlValidationError::PrivateOrLocalHost { .. }));
     }
 
     #[test]
     fn redirect_target_validation_allows_permitted_host() {
-        let allowed = vec!["example.com".to_string()];
-        let blocked = vec![];
+        let tool = test_tool(vec!["example.com"]);
         assert!(
-            validate_target_url(
-                "https://docs.example.com/page",
-                &allowed,
-                &blocked,
-                &[],
-                "web_fetch"
-            )
-            .is_ok()
+            tool.policy
+                .validate("https://docs.example.com/page")
+                .is_ok()
         );
     }
 
     #[test]
     fn redirect_target_validation_blocks_private_host() {
-        let allowed = vec!["example.com".to_string()];
-        let blocked = vec![];
-        let err = validate_target_url(
-            "https://127.0.0.1/admin",
-            &allowed,
-            &blocked,
-            &[],
-            "web_fetch",
-        )
-        .unwrap_err()
-        .to_string();
-        assert!(err.contains("local/private"));
+        let tool = test_tool(vec!["example.com"]);
+        let err = tool.policy.validate("https://127.0.0.1/admin").unwrap_err();
+        assert!(matches!(err, UrlValidationError::PrivateOrLocalHost { .. }));
     }
 
     #[test]
     fn redirect_target_validation_blocks_blocklisted_host() {
-        let allowed = vec!["*".to_string()];
-        let blocked = vec!["evil.com".to_string()];
-        let err = validate_target_url(
-            "https://evil.com/phish",
-            &allowed,
-            &blocked,
-            &[],
-            "web_fetch",
-        )
-        .unwrap_err()
-        .to_string();
-        assert!(err.contains("blocked_domains"));
+        let tool = test_tool_with_blocklist(vec!["*"], vec!["evil.com"]);
+        let err = tool.policy.validate("https://evil.com/phish").unwrap_err();
+        assert_eq!(
+            err,
+            UrlValidationError::BlockedDomain {
+                host: "evil.com".into()
+            }
+        );
     }
 
     // ── Security policy ──────────────────────────────────────────
@@ -1017,31 +1306,22 @@ mod tests {
     #[test]
     fn blocklist_rejects_exact_match() {
         let tool = test_tool_with_blocklist(vec!["*"], vec!["evil.com"]);
-        let err = tool
-            .validate_url("https://evil.com/page")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("blocked_domains"));
+        let err = tool.validate_url("https://evil.com/page").unwrap_err();
+        assert!(matches!(err, UrlValidationError::BlockedDomain { .. }));
     }
 
     #[test]
     fn blocklist_rejects_subdomain() {
         let tool = test_tool_with_blocklist(vec!["*"], vec!["evil.com"]);
-        let err = tool
-            .validate_url("https://api.evil.com/v1")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("blocked_domains"));
+        let err = tool.validate_url("https://api.evil.com/v1").unwrap_err();
+        assert!(matches!(err, UrlValidationError::BlockedDomain { .. }));
     }
 
     #[test]
     fn blocklist_wins_over_allowlist() {
         let tool = test_tool_with_blocklist(vec!["evil.com"], vec!["evil.com"]);
-        let err = tool
-            .validate_url("https://evil.com")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("blocked_domains"));
+        let err = tool.validate_url("https://evil.com").unwrap_err();
+        assert!(matches!(err, UrlValidationError::BlockedDomain { .. }));
     }
 
     #[test]
@@ -1061,10 +1341,11 @@ mod tests {
     #[test]
     fn resolved_private_ip_is_rejected() {
         let ips = vec!["127.0.0.1".parse().unwrap()];
-        let err = validate_resolved_ips_are_public("example.com", &ips)
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("non-global address"));
+        let err = validate_resolved_ips_are_public("example.com", &ips).unwrap_err();
+        assert!(matches!(
+            err,
+            UrlValidationError::ResolvesToNonGlobal { .. }
+        ));
     }
 
     #[test]
@@ -1073,10 +1354,11 @@ mod tests {
             "93.184.216.34".parse().unwrap(),
             "10.0.0.1".parse().unwrap(),
         ];
-        let err = validate_resolved_ips_are_public("example.com", &ips)
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("non-global address"));
+        let err = validate_resolved_ips_are_public("example.com", &ips).unwrap_err();
+        assert!(matches!(
+            err,
+            UrlValidationError::ResolvesToNonGlobal { .. }
+        ));
     }
 
     #[test]
@@ -1361,7 +1643,10 @@ mod tests {
             .unwrap();
 
         let url = format!("http://{addr}/page");
-        let standard_result = tool.standard_fetch(&client, &url).await;
+        let standard_result = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap();
 
         // standard_fetch should fail with 403
         assert!(!standard_result.success);
@@ -1450,7 +1735,10 @@ mod tests {
             .unwrap();
 
         let url = format!("http://{standard_addr}/page");
-        let standard_result = tool.standard_fetch(&client, &url).await;
+        let standard_result = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap();
 
         // Standard fetch returns short body, should trigger fallback
         assert!(tool.should_fallback_to_firecrawl(&standard_result));
@@ -1481,23 +1769,19 @@ mod tests {
     #[test]
     fn unallowed_private_host_still_blocked() {
         let tool = test_tool_with_private_hosts(vec!["*"], vec![], vec!["192.168.1.5"]);
-        let err = tool
-            .validate_url("https://10.0.0.1/admin")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("local/private"));
-        assert!(err.contains("allowed_private_hosts"));
+        let err = tool.validate_url("https://10.0.0.1/admin").unwrap_err();
+        assert!(matches!(err, UrlValidationError::PrivateOrLocalHost { .. }));
+        let msg = describe_url_error(&err);
+        assert!(msg.contains("allowed_private_hosts"));
+        assert!(!msg.contains("Add this domain"));
     }
 
     #[test]
     fn blocklist_overrides_allowed_private_host() {
         let tool =
             test_tool_with_private_hosts(vec!["*"], vec!["192.168.1.5"], vec!["192.168.1.5"]);
-        let err = tool
-            .validate_url("https://192.168.1.5/secret")
-            .unwrap_err()
-            .to_string();
-        assert!(err.contains("blocked_domains"));
+        let err = tool.validate_url("https://192.168.1.5/secret").unwrap_err();
+        assert!(matches!(err, UrlValidationError::BlockedDomain { .. }));
     }
 
     #[test]
@@ -1505,4 +1789,1136 @@ mod tests {
         let tool = test_tool_with_private_hosts(vec!["*"], vec![], vec!["192.168.1.5"]);
         assert!(tool.validate_url("https://192.168.1.5:8080/api").is_ok());
     }
+
+    // ── Redirect hop validation ─────────────────────────────────────
+
+    fn redirect_test_tool(allowed_domains: Vec<&str>) -> WebFetchTool {
+        test_tool_with_private_hosts(allowed_domains, vec![], vec!["127.0.0.1"])
+    }
+
+    fn redirect_to(location: &str) -> wiremock::ResponseTemplate {
+        wiremock::ResponseTemplate::new(302).insert_header("location", location)
+    }
+
+    #[test]
+    fn redirect_hop_resolves_relative_location() {
+        let policy = UrlPolicy::new(SchemeConstraint::HttpOrHttps, vec!["example.com".into()]);
+        let next = resolve_redirect_hop(&policy, "https://example.com/docs/a", b"b?x=1", 1, false)
+            .unwrap();
+        assert_eq!(next.as_str(), "https://example.com/docs/b?x=1");
+        let next = resolve_redirect_hop(&policy, "https://example.com/docs/a", b"/root", 1, false)
+            .unwrap();
+        assert_eq!(next.as_str(), "https://example.com/root");
+    }
+
+    #[test]
+    fn redirect_hop_tolerates_malformed_locations() {
+        let policy = UrlPolicy::new(
+            SchemeConstraint::HttpOrHttps,
+            vec!["example.com".into(), "cdn.example.net".into()],
+        );
+        let base = "https://example.com/docs/page?q=1";
+        let cases: &[(&str, &[u8], &str)] = &[
+            (
+                "unencoded spaces",
+                b"/search results/a b?q=x y",
+                "https://example.com/search%20results/a%20b?q=x%20y",
+            ),
+            (
+                "raw UTF-8 path",
+                "/caf\u{e9}/men\u{fc}".as_bytes(),
+                "https://example.com/caf%C3%A9/men%C3%BC",
+            ),
+            ("Latin-1 byte", b"/caf\xe9", "https://example.com/caf%E9"),
+            (
+                "no leading slash",
+                b"other?x=1",
+                "https://example.com/docs/other?x=1",
+            ),
+            ("parent segment", b"../up", "https://example.com/up"),
+            (
+                "scheme-relative",
+                b"//cdn.example.net/asset",
+                "https://cdn.example.net/asset",
+            ),
+            (
+                "query only",
+                b"?page=2",
+                "https://example.com/docs/page?page=2",
+            ),
+            (
+                "surrounding whitespace",
+                b"  /trimmed\t",
+                "https://example.com/trimmed",
+            ),
+            (
+                "unsafe ASCII",
+                b"/a<b>\"c\"{d}|e",
+                "https://example.com/a%3Cb%3E%22c%22%7Bd%7D%7Ce",
+            ),
+            (
+                "already encoded",
+                b"/a%20b?x=%2F",
+                "https://example.com/a%20b?x=%2F",
+            ),
+        ];
+
+        for (name, location, expected) in cases {
+            let next = resolve_redirect_hop(&policy, base, location, 1, false)
+                .unwrap_or_else(|e| panic!("{name}: {e}"));
+            assert_eq!(next.as_str(), *expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn redirect_hop_rejects_unusable_locations_with_raw_value() {
+        let policy = UrlPolicy::new(SchemeConstraint::HttpOrHttps, vec!["example.com".into()]);
+        let base = "https://example.com/a";
+        let cases: &[(&str, &[u8], &str)] = &[
+            ("empty", b"   ", "Location header is empty"),
+            ("bad IPv6 literal", b"http://[::1", "http://[::1"),
+            ("empty host", b"https://", "https://"),
+            ("bad port", b"https://example.com:99999/", "99999"),
+            ("normalized but off-policy", b"//evil.test/a b", "evil.test"),
+        ];
+
+        for (name, location, needle) in cases {
+            let err = resolve_redirect_hop(&policy, base, location, 3, false)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains("hop 3"), "{name}: {err}");
+            assert!(err.contains(needle), "{name}: {err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn redirect_with_duplicate_location_follows_the_first() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/start"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .append_header("location", "/first page")
+                    .append_header("location", "/second"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/first%20page"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("first"))
+            .mount(&server)
+            .await;
+
+        let tool = redirect_test_tool(vec!["example.com"]);
+        let client = tool.build_client().unwrap();
+        let url = format!("http://{}/start", server.address());
+        let result = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "first");
+    }
+
+    #[test]
+    fn redirect_hop_rejects_https_downgrade_under_https_only() {
+        let policy = UrlPolicy::new(SchemeConstraint::HttpsOnly, vec!["example.com".into()]);
+        let err = resolve_redirect_hop(
+            &policy,
+            "https://example.com/a",
+            b"http://example.com/b",
+            1,
+            false,
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("hop 1"), "{err}");
+        assert!(err.contains("Only https:// URLs are allowed"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn redirect_follows_relative_location_to_final_page() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/start"))
+            .respond_with(redirect_to("next"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/next"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string("arrived")
+                    .insert_header("content-type", "text/plain"),
+            )
+            .mount(&server)
+            .await;
+
+        let tool = redirect_test_tool(vec!["example.com"]);
+        let client = tool.build_client().unwrap();
+        let url = format!("http://{}/start", server.address());
+        let result = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "arrived");
+    }
+
+    #[tokio::test]
+    async fn redirect_to_metadata_endpoint_is_blocked() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/start"))
+            .respond_with(redirect_to("http://169.254.169.254/latest/meta-data/"))
+            .mount(&server)
+            .await;
+
+        let tool = redirect_test_tool(vec!["*"]);
+        let client = tool.build_client().unwrap();
+        let url = format!("http://{}/start", server.address());
+        let result = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        let err = result.error.unwrap();
+        assert!(err.contains("hop 1"), "{err}");
+        assert!(err.contains("169.254.169.254"), "{err}");
+        assert!(err.contains("local/private"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn redirect_to_non_allowlisted_host_is_blocked() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/start"))
+            .respond_with(redirect_to("https://evil.example.org/phish"))
+            .mount(&server)
+            .await;
+
+        let tool = redirect_test_tool(vec!["example.com"]);
+        let client = tool.build_client().unwrap();
+        let url = format!("http://{}/start", server.address());
+        let err = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap()
+            .error
+            .unwrap();
+        assert!(err.contains("hop 1"), "{err}");
+        assert!(err.contains("evil.example.org"), "{err}");
+        assert!(err.contains("web_fetch.allowed_domains"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn redirect_chain_stops_at_blocked_intermediate_hop() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // /start -> /hop -> localhost/middle -> 127.0.0.1/final. The mock
+        // serves every hop, so only the policy decides where the chain ends.
+        async fn chain() -> MockServer {
+            let server = MockServer::start().await;
+            let port = server.address().port();
+            let hops = [
+                ("/start", "/hop".to_string()),
+                ("/hop", format!("http://localhost:{port}/middle")),
+                ("/middle", format!("http://127.0.0.1:{port}/final")),
+            ];
+            for (from, to) in hops {
+                Mock::given(method("GET"))
+                    .and(path(from))
+                    .respond_with(redirect_to(&to))
+                    .mount(&server)
+                    .await;
+            }
+            Mock::given(method("GET"))
+                .and(path("/final"))
+                .respond_with(ResponseTemplate::new(200).set_body_string("final"))
+                .mount(&server)
+                .await;
+            server
+        }
+
+        async fn requested_paths(server: &MockServer) -> Vec<String> {
+            server
+                .received_requests()
+                .await
+                .unwrap()
+                .iter()
+                .map(|r| r.url.path().to_string())
+                .collect()
+        }
+
+        // Control: with the intermediate host permitted, the chain completes.
+        let server = chain().await;
+        let url = format!("http://{}/start", server.address());
+        let open = test_tool_with_private_hosts(vec!["*"], vec![], vec!["127.0.0.1", "localhost"]);
+        let result = open
+            .standard_fetch(&open.build_client().unwrap(), &validated(&url))
+            .await
+            .unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "final");
+        assert_eq!(
+            requested_paths(&server).await,
+            ["/start", "/hop", "/middle", "/final"]
+        );
+
+        // Only the intermediate host is refused; the final target is the
+        // same 127.0.0.1 the chain started on.
+        let server = chain().await;
+        let url = format!("http://{}/start", server.address());
+        let tool = redirect_test_tool(vec!["*"]);
+        let err = tool
+            .standard_fetch(&tool.build_client().unwrap(), &validated(&url))
+            .await
+            .unwrap()
+            .error
+            .unwrap();
+        assert!(err.contains("Blocked redirect at hop 2"), "{err}");
+        assert!(
+            err.contains("Blocked local/private host: localhost"),
+            "{err}"
+        );
+        assert_eq!(requested_paths(&server).await, ["/start", "/hop"]);
+    }
+
+    #[tokio::test]
+    async fn redirect_errors_redact_url_fragments() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/start"))
+            .respond_with(redirect_to("http://10.0.0.1/cb#access_token=SECRET123"))
+            .mount(&server)
+            .await;
+        let url = format!("http://{}/start#state=SECRET456", server.address());
+
+        let tool = redirect_test_tool(vec!["*"]);
+        let err = tool
+            .standard_fetch(&tool.build_client().unwrap(), &validated(&url))
+            .await
+            .unwrap()
+            .error
+            .unwrap();
+        assert!(!err.contains("SECRET"), "{err}");
+        assert!(
+            err.contains("/start#[redacted] -> http://10.0.0.1/cb#[redacted]"),
+            "{err}"
+        );
+
+        let debug = redirect_test_tool(vec!["*"]).with_log_fragments(true);
+        let err = debug
+            .standard_fetch(&debug.build_client().unwrap(), &validated(&url))
+            .await
+            .unwrap()
+            .error
+            .unwrap();
+        assert!(err.contains("#access_token=SECRET123"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn redirect_loop_is_detected() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/a"))
+            .respond_with(redirect_to("/b"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/b"))
+            .respond_with(redirect_to("/a"))
+            .mount(&server)
+            .await;
+
+        let tool = redirect_test_tool(vec!["*"]);
+        let client = tool.build_client().unwrap();
+        let url = format!("http://{}/a", server.address());
+        let err = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap()
+            .error
+            .unwrap();
+        assert!(err.contains("Redirect loop detected at hop 2"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn redirect_limit_names_offending_hop() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer};
+
+        let server = MockServer::start().await;
+        for (from, to) in [("/1", "/2"), ("/2", "/3"), ("/3", "/4")] {
+            Mock::given(method("GET"))
+                .and(path(from))
+                .respond_with(redirect_to(to))
+                .mount(&server)
+                .await;
+        }
+
+        let tool = redirect_test_tool(vec!["*"]).with_max_redirects(2);
+        let client = tool.build_client().unwrap();
+        let url = format!("http://{}/1", server.address());
+        let err = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap()
+            .error
+            .unwrap();
+        assert!(err.contains("Too many redirects (max 2)"), "{err}");
+        assert!(err.contains("hop 3"), "{err}");
+        assert!(err.contains("/4"), "{err}");
+    }
+
+    // ── Pluggable HTML conversion ───────────────────────────────────
+
+    #[tokio::test]
+    async fn html_is_converted_while_streaming_and_capped() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let page = format!(
+            "<html><head><script>{}</script></head><body><h1>Top</h1>{}</body></html>",
+            "x=1;".repeat(10_000),
+            "<p>para</p>".repeat(50_000)
+        );
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(page, "text/html"))
+            .mount(&server)
+            .await;
+
+        let tool = test_tool_with_private_hosts(vec!["example.com"], vec![], vec!["127.0.0.1"]);
+        let client = tool.build_client().unwrap();
+        let url = format!("http://{}/big", server.address());
+        let result = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert!(result.output.starts_with("Top\n\npara\n\npara"));
+        assert!(!result.output.contains("x=1"));
+    }
+
+    #[tokio::test]
+    async fn streamed_html_keeps_link_targets() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                r#"<p>Read the <a href="https://docs.example.com/guide">guide</a> first.</p>"#,
+                "text/html",
+            ))
+            .mount(&server)
+            .await;
+
+        let tool = test_tool_with_private_hosts(vec!["example.com"], vec![], vec!["127.0.0.1"]);
+        let client = tool.build_client().unwrap();
+        let url = format!("http://{}/links", server.address());
+        let result = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert!(
+            result
+                .output
+                .starts_with("Read the guide (https://docs.example.com/guide) first."),
+            "{}",
+            result.output
+        );
+    }
+
+    struct RecordingConverter {
+        seen_base: parking_lot::Mutex<Option<String>>,
+    }
+
+    impl HtmlConverter for RecordingConverter {
+        fn convert(
+            &self,
+            html: &str,
+            base_url: &ValidatedUrl,
+            opts: &ConvertOptions,
+        ) -> anyhow::Result<crate::tools::html_converter::ConvertedDoc> {
+            *self.seen_base.lock() = Some(base_url.as_str().to_string());
+            Ok(crate::tools::html_converter::ConvertedDoc {
+                text: format!("custom:{}:{}", html.len(), opts.max_output_chars),
+                warnings: vec!["recorded".into()],
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_html_converter_is_used_with_final_url() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/start"))
+            .respond_with(redirect_to("/page"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/page"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<p>hi</p>", "text/html"))
+            .mount(&server)
+            .await;
+
+        let converter = Arc::new(RecordingConverter {
+            seen_base: parking_lot::Mutex::new(None),
+        });
+        let tool = redirect_test_tool(vec!["example.com"]).with_html_converter(converter.clone());
+        let client = tool.build_client().unwrap();
+        let url = format!("http://{}/start", server.address());
+        let result = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "custom:9:500000");
+        assert_eq!(
+            converter.seen_base.lock().as_deref(),
+            Some(format!("http://{}/page", server.address()).as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn html_converter_error_fails_the_fetch() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        struct FailingConverter;
+        impl HtmlConverter for FailingConverter {
+            fn convert(
+                &self,
+                _html: &str,
+                _base_url: &ValidatedUrl,
+                _opts: &ConvertOptions,
+            ) -> anyhow::Result<crate::tools::html_converter::ConvertedDoc> {
+                anyhow::bail!("unsupported markup")
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw("<p>hi</p>", "text/html"))
+            .mount(&server)
+            .await;
+
+        let tool =
+            redirect_test_tool(vec!["example.com"]).with_html_converter(Arc::new(FailingConverter));
+        let client = tool.build_client().unwrap();
+        let url = format!("http://{}/page", server.address());
+        let err = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap()
+            .error
+            .unwrap();
+        assert!(err.contains("unsupported markup"), "{err}");
+    }
+
+    // ── Empty bodies ────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn empty_bodies_are_described_instead_of_blank() {
+        use wiremock::matchers::path;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let cases: &[(&str, u16, &[u8], &str, &str)] = &[
+            (
+                "/no-content",
+                204,
+                b"",
+                "text/html",
+                "(empty response, status 204)",
+            ),
+            (
+                "/empty",
+                200,
+                b"",
+                "text/plain",
+                "(empty response, status 200)",
+            ),
+            (
+                "/empty-html",
+                200,
+                b"",
+                "text/html",
+                "(empty response, status 200)",
+            ),
+            (
+                "/bom",
+                200,
+                b"\xEF\xBB\xBF",
+                "text/html",
+                "(empty response, status 200; body was 3 bytes of whitespace/BOM)",
+            ),
+            (
+                "/space",
+                200,
+                b" ",
+                "application/json",
+                "(empty response, status 200; body was 1 bytes of whitespace/BOM)",
+            ),
+            (
+                "/bom-text",
+                200,
+                b"\xEF\xBB\xBFhello",
+                "text/plain",
+                "hello",
+            ),
+            (
+                "/bom-html",
+                200,
+                b"\xEF\xBB\xBF<p>hello</p>",
+                "text/html",
+                "hello",
+            ),
+        ];
+
+        let server = MockServer::start().await;
+        for (route, status, body, content_type, _) in cases {
+            Mock::given(path(*route))
+                .respond_with(
+                    ResponseTemplate::new(*status).set_body_raw(body.to_vec(), content_type),
+                )
+                .mount(&server)
+                .await;
+        }
+
+        let tool = redirect_test_tool(vec!["example.com"]);
+        let client = tool.build_client().unwrap();
+        for (route, _, _, _, expected) in cases {
+            let url = format!("http://{}{route}", server.address());
+            let result = tool
+                .standard_fetch(&client, &validated(&url))
+                .await
+                .unwrap();
+            assert!(result.success, "{route}: {:?}", result.error);
+            assert_eq!(result.output.trim(), *expected, "{route}");
+        }
+    }
+
+    // ── Pre-send interception ───────────────────────────────────────
+
+    #[derive(Default)]
+    struct LegalHoldTagger {
+        seen: parking_lot::Mutex<Vec<(String, String)>>,
+    }
+
+    impl crate::tools::pre_send::PreSendInterceptor for LegalHoldTagger {
+        fn intercept(
+            &self,
+            request: &crate::tools::pre_send::OutboundRequest<'_>,
+        ) -> anyhow::Result<crate::tools::pre_send::InterceptDecision> {
+            let user_agent = request.headers[reqwest::header::USER_AGENT].to_str()?;
+            self.seen
+                .lock()
+                .push((request.url.path().to_string(), user_agent.to_string()));
+            Ok(crate::tools::pre_send::InterceptDecision::Modify(vec![(
+                "X-Legal-Hold".into(),
+                "case-7".into(),
+            )]))
+        }
+    }
+
+    #[tokio::test]
+    async fn interceptor_runs_once_per_hop_and_its_header_reaches_the_wire() {
+        use wiremock::matchers::{header, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/start"))
+            .and(header("x-legal-hold", "case-7"))
+            .respond_with(redirect_to("/next"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/next"))
+            .and(header("x-legal-hold", "case-7"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("tagged"))
+            .mount(&server)
+            .await;
+
+        let tagger = Arc::new(LegalHoldTagger::default());
+        let tool =
+            redirect_test_tool(vec!["example.com"]).with_pre_send_interceptor(tagger.clone());
+        let client = tool.build_client().unwrap();
+        let url = format!("http://{}/start", server.address());
+        let result = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap();
+
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output, "tagged");
+        assert_eq!(
+            *tagger.seen.lock(),
+            vec![
+                ("/start".to_string(), USER_AGENT.to_string()),
+                ("/next".to_string(), USER_AGENT.to_string()),
+            ]
+        );
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn interceptor_denial_stops_the_redirect_chain() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer};
+
+        struct DenyNext;
+        impl crate::tools::pre_send::PreSendInterceptor for DenyNext {
+            fn intercept(
+                &self,
+                request: &crate::tools::pre_send::OutboundRequest<'_>,
+            ) -> anyhow::Result<crate::tools::pre_send::InterceptDecision> {
+                Ok(if request.url.path() == "/next" {
+                    crate::tools::pre_send::InterceptDecision::Deny("partner on hold".into())
+                } else {
+                    crate::tools::pre_send::InterceptDecision::Allow
+                })
+            }
+        }
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/start"))
+            .respond_with(redirect_to("/next"))
+            .mount(&server)
+            .await;
+
+        let tool =
+            redirect_test_tool(vec!["example.com"]).with_pre_send_interceptor(Arc::new(DenyNext));
+        let client = tool.build_client().unwrap();
+        let url = format!("http://{}/start", server.address());
+        let denied = tool
+            .standard_fetch(&client, &validated(&url))
+            .await
+            .unwrap_err();
+
+        assert_eq!(denied.reason, "partner on hold");
+        let paths: Vec<_> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.url.path().to_string())
+            .collect();
+        assert_eq!(paths, vec!["/start"]);
+    }
+
+    #[tokio::test]
+    async fn interceptor_denial_is_not_bypassed_by_firecrawl_fallback() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        struct DenyPath(&'static str);
+        impl crate::tools::pre_send::PreSendInterceptor for DenyPath {
+            fn intercept(
+                &self,
+                request: &crate::tools::pre_send::OutboundRequest<'_>,
+            ) -> anyhow::Result<crate::tools::pre_send::InterceptDecision> {
+                Ok(if request.url.path() == self.0 {
+                    crate::tools::pre_send::InterceptDecision::Deny("partner on hold".into())
+                } else {
+                    crate::tools::pre_send::InterceptDecision::Allow
+                })
+            }
+        }
+
+        // SAFETY: test-only, single-threaded test runner.
+        unsafe { std::env::set_var("FIRECRAWL_DENIAL_TEST_KEY", "test-key") };
+
+        // Denying the page itself, and denying the Firecrawl call made for a
+        // page too short to use, must both keep Firecrawl from being asked.
+        for denied_path in ["/page", "/scrape"] {
+            let standard_server = MockServer::start().await;
+            Mock::given(method("GET"))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_string("<html><body>Loading...</body></html>")
+                        .insert_header("content-type", "text/html"),
+                )
+                .mount(&standard_server)
+                .await;
+            let firecrawl_server = MockServer::start().await;
+            Mock::given(method("POST"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "success": true,
+                    "data": { "markdown": "# Fetched anyway" }
+                })))
+                .mount(&firecrawl_server)
+                .await;
+
+            let tool = WebFetchTool::new(
+                Arc::new(SecurityPolicy::default()),
+                vec!["*".into()],
+                vec![],
+                500_000,
+                30,
+                FirecrawlConfig {
+                    enabled: true,
+                    api_key_env: "FIRECRAWL_DENIAL_TEST_KEY".into(),
+                    api_url: format!("http://{}", firecrawl_server.address()),
+                    ..FirecrawlConfig::default()
+                },
+                vec!["127.0.0.1".into()],
+            )
+            .with_pre_send_interceptor(Arc::new(DenyPath(denied_path)));
+
+            let result = tool
+                .execute(json!({ "url": format!("http://{}/page", standard_server.address()) }))
+                .await
+                .unwrap();
+
+            assert!(
+                firecrawl_server
+                    .received_requests()
+                    .await
+                    .unwrap()
+                    .is_empty(),
+                "{denied_path}"
+            );
+            if denied_path == "/page" {
+                assert!(!result.success);
+                let err = result.error.unwrap();
+                assert!(
+                    err.contains("denied by pre-send interceptor: partner on hold"),
+                    "{err}"
+                );
+                assert!(err.ends_with("Do not retry this request."), "{err}");
+                assert!(
+                    standard_server
+                        .received_requests()
+                        .await
+                        .unwrap()
+                        .is_empty(),
+                    "{denied_path}"
+                );
+            } else {
+                assert!(result.success, "{:?}", result.error);
+                assert!(result.output.contains("Loading..."), "{}", result.output);
+            }
+        }
+
+        // SAFETY: test-only, single-threaded test runner.
+        unsafe { std::env::remove_var("FIRECRAWL_DENIAL_TEST_KEY") };
+    }
+
+    // ── 429 cooldowns ───────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn too_many_requests_cools_down_host_for_both_network_tools() {
+        use crate::tools::http_request::HttpRequestTool;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "60"))
+            .mount(&server)
+            .await;
+
+        let cooldowns = Arc::new(HostCooldowns::new());
+        let fetch = redirect_test_tool(vec!["example.com"]).with_host_cooldowns(cooldowns.clone());
+        let http = HttpRequestTool::new(
+            Arc::new(SecurityPolicy::default()),
+            vec!["*".into()],
+            1_000_000,
+            30,
+            true,
+        )
+        .with_host_cooldowns(cooldowns.clone());
+        let args = json!({"url": format!("http://{}/items", server.address())});
+
+        let first = fetch.execute(args.clone()).await.unwrap();
+        assert!(!first.success);
+        assert!(
+            first.output.starts_with("cooldown: 127.0.0.1 for "),
+            "{}",
+            first.output
+        );
+        for refused in [
+            http.execute(args.clone()).await.unwrap(),
+            fetch.execute(args.clone()).await.unwrap(),
+        ] {
+            assert_eq!(refused.output.lines().count(), 1, "{}", refused.output);
+            assert!(
+                refused.output.ends_with("(HTTP 429 responses: 1)"),
+                "{}",
+                refused.output
+            );
+        }
+        // Estimates report the same refusal without sending anything.
+        for message in [
+            http.estimate(&args).blocked.unwrap(),
+            http.execute(args.clone()).await.unwrap().error.unwrap(),
+            fetch.estimate(&args).blocked.unwrap(),
+            fetch.execute(args.clone()).await.unwrap().error.unwrap(),
+        ] {
+            assert!(message.contains("cooling down"), "{message}");
+            assert!(
+                message.ends_with("Wait 60s, then retry.")
+                    || message.ends_with("Wait 59s, then retry."),
+                "{message}"
+            );
+        }
+        for estimate in [http.estimate(&args), fetch.estimate(&args)] {
+            assert!(!estimate.may_prompt_approval);
+            let Some(ShortCircuit::Cooldown { remaining }) = estimate.short_circuit else {
+                panic!("{estimate:?}");
+            };
+            assert!(remaining > Duration::from_secs(58), "{remaining:?}");
+        }
+
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+        let report = cooldowns.report();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].host.as_str(), "127.0.0.1");
+        assert_eq!(report[0].incurred, 1);
+        assert!(report[0].remaining.is_some());
+    }
+
+    // ── Retry guidance ──────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn errors_end_with_retry_guidance() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/gone"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/busy"))
+            .respond_with(ResponseTemplate::new(503).insert_header("retry-after", "30"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/away"))
+            .respond_with(redirect_to("https://other.example/"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/binary"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "application/octet-stream")
+                    .set_body_bytes(vec![0u8; 16]),
+            )
+            .mount(&server)
+            .await;
+
+        let tool = redirect_test_tool(vec!["example.com"]);
+        let base = format!("http://{}", server.address());
+        let cases = [
+            (
+                format!("{base}/gone"),
+                "HTTP 404 Not Found. Do not retry this URL; use a different one.",
+            ),
+            (
+                format!("{base}/busy"),
+                "HTTP 503 Service Unavailable. Wait 30s, then retry.",
+            ),
+            (
+                "https://other.example/".to_string(),
+                "Do not retry; ask the operator to add this host to allowed_domains.",
+            ),
+            (
+                format!("{base}/away"),
+                "Do not retry; ask the operator to add this host to allowed_domains.",
+            ),
+            (
+                format!("{base}/binary"),
+                "Do not retry this URL; use a different one.",
+            ),
+            (
+                "ftp://example.com/".to_string(),
+                "Do not retry this URL; use a different one.",
+            ),
+        ];
+
+        for (url, suffix) in cases {
+            let result = tool.execute(json!({"url": url})).await.unwrap();
+            let err = result.error.unwrap();
+            assert!(err.ends_with(suffix), "{url}: {err}");
+        }
+    }
+
+    // ── Truncated transfers ─────────────────────────────────────────
+
+    #[tokio::test]
+    async fn early_close_fails_instead_of_returning_a_partial_page() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let cases: [(&[u8], &str); 2] = [
+            (
+                b"HTTP/1.1 200 OK\r\ncontent-type: text/plain\r\ncontent-length: 100\r\n\r\n\
+                  half an article",
+                "received 15 of 100 bytes declared by Content-Length",
+            ),
+            (
+                b"HTTP/1.1 200 OK\r\ncontent-type: text/html\r\ntransfer-encoding: chunked\r\n\r\n\
+                  f\r\n<p>half an arti\r\n",
+                "chunked body ended after 15 bytes",
+            ),
+        ];
+
+        for (response, detail) in cases {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            let server = tokio::spawn(async move {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut received = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !received.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    received.extend_from_slice(&buf[..n]);
+                }
+                socket.write_all(response).await.unwrap();
+                socket.shutdown().await.unwrap();
+            });
+
+            let tool = redirect_test_tool(vec!["example.com"]);
+            let result = tool
+                .execute(json!({"url": format!("http://{addr}/article")}))
+                .await
+                .unwrap();
+            server.await.unwrap();
+
+            assert!(!result.success);
+            let err = result.error.unwrap();
+            assert!(err.contains(detail), "{err}");
+            assert!(err.ends_with("Wait a while, then retry."), "{err}");
+            assert!(
+                result.output.starts_with("truncated_transfer: true"),
+                "{}",
+                result.output
+            );
+            assert!(!result.output.contains("half an arti"), "{}", result.output);
+        }
+    }
+
+    // ── Cost estimation ─────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn estimate_bounds_actual_requests_for_redirected_fetch() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/a"))
+            .respond_with(redirect_to("/b"))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/b"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("done"))
+            .mount(&server)
+            .await;
+
+        let tool = redirect_test_tool(vec!["example.com"]).with_max_redirects(2);
+        let args = json!({"url": format!("http://{}/a", server.address())});
+        let estimate = tool.estimate(&args);
+        assert_eq!(estimate.blocked, None);
+        assert_eq!(estimate.request_count, 1);
+        assert_eq!(estimate.max_request_count, 3);
+        assert_eq!(estimate.worst_case_bytes, Some(500_001));
+
+        let result = tool.execute(args).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        let sent = server.received_requests().await.unwrap().len();
+        assert!(
+            (estimate.request_count..=estimate.max_request_count).contains(&sent),
+            "sent {sent} requests, estimated {estimate:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn estimate_reports_the_same_refusal_as_execute() {
+        let read_only = WebFetchTool::new(
+            Arc::new(SecurityPolicy {
+                autonomy: AutonomyLevel::ReadOnly,
+                ..SecurityPolicy::default()
+            }),
+            vec!["example.com".into()],
+            vec![],
+            500_000,
+            30,
+            FirecrawlConfig::default(),
+            vec![],
+        );
+        let rate_limited = WebFetchTool::new(
+            Arc::new(SecurityPolicy {
+                max_actions_per_hour: 0,
+                ..SecurityPolicy::default()
+            }),
+            vec!["example.com".into()],
+            vec![],
+            500_000,
+            30,
+            FirecrawlConfig::default(),
+            vec![],
+        );
+        let cases = [
+            (read_only, "https://example.com"),
+            (rate_limited, "https://example.com"),
+            (test_tool(vec!["example.com"]), "https://other.org"),
+            (test_tool(vec!["example.com"]), "http://10.0.0.1"),
+        ];
+
+        for (tool, url) in cases {
+            let args = json!({"url": url});
+            let estimate = tool.estimate(&args);
+            assert_eq!(estimate.max_request_count, 0);
+            assert_eq!(estimate.may_prompt_approval, url == "https://other.org");
+            let result = tool.execute(args).await.unwrap();
+            assert_eq!(estimate.blocked, result.error, "{url}");
+        }
+    }
+
+    #[test]
+    fn estimate_is_unbounded_with_firecrawl_fallback() {
+        let tool = test_tool_with_firecrawl(FirecrawlConfig {
+            enabled: true,
+            ..FirecrawlConfig::default()
+        });
+        let estimate = tool.estimate(&json!({"url": "https://example.com"}));
+        assert_eq!(estimate.max_request_count, 1 + DEFAULT_MAX_REDIRECTS + 1);
+        assert_eq!(estimate.worst_case_bytes, None);
+    }
 }
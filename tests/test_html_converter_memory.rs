@@ -0,0 +1,116 @@
+//! Peak-memory check for the streaming HTML converter.
+//!
+//! Lives in its own test binary because it installs a counting
+//! `#[global_allocator]`, which would otherwise apply to every test linked
+//! alongside it.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use zeroclaw::tools::html_converter::StreamingTextConverter;
+
+/// Allocator that tracks live and peak heap bytes for the threads that opt
+/// in through [`peak_heap`], so concurrent tests do not skew it.
+struct CountingAllocator;
+
+thread_local! {
+    static TRACKING: Cell<bool> = const { Cell::new(false) };
+    static LIVE: Cell<isize> = const { Cell::new(0) };
+    static PEAK: Cell<isize> = const { Cell::new(0) };
+}
+
+fn record(delta: isize) {
+    let _ = TRACKING.try_with(|tracking| {
+        if tracking.get() {
+            let live = LIVE.get() + delta;
+            LIVE.set(live);
+            PEAK.set(PEAK.get().max(live));
+        }
+    });
+}
+
+#[allow(clippy::cast_possible_wrap)]
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            record(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        record(-(layout.size() as isize));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            record(new_size as isize - layout.size() as isize);
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Run `f` and return its result with the peak heap bytes it held.
+#[allow(clippy::cast_sign_loss)]
+fn peak_heap<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    LIVE.set(0);
+    PEAK.set(0);
+    TRACKING.set(true);
+    let result = f();
+    TRACKING.set(false);
+    (result, PEAK.get().max(0) as usize)
+}
+
+#[test]
+fn streaming_converter_peak_memory_is_bounded_by_output_not_input() {
+    const CHUNK: usize = 16 * 1024;
+    const OVERHEAD: usize = 512 * 1024;
+    let paragraph = "<p class=\"x\">Lorem ipsum dolor sit amet &amp; more.</p>\n";
+    let page = format!(
+        "<html><body><script>{}</script><!-- {} -->{}</body></html>",
+        "var x = 1;".repeat(50_000),
+        "-".repeat(100_000),
+        paragraph.repeat(100_000)
+    );
+    assert!(page.len() > 4 * 1024 * 1024);
+    let chunks: Vec<&[u8]> = page.as_bytes().chunks(CHUNK).collect();
+
+    // The buffered path collects the whole body, then converts it in one go.
+    let (buffered_text, buffered_peak) = peak_heap(|| {
+        let mut body = Vec::new();
+        for chunk in &chunks {
+            body.extend_from_slice(chunk);
+        }
+        nanohtml2text::html2text(&String::from_utf8_lossy(&body))
+    });
+    assert!(buffered_text.contains("Lorem ipsum dolor sit amet & more."));
+    assert!(buffered_peak >= page.len());
+
+    let stream = |max_output_chars: usize| {
+        let mut converter = StreamingTextConverter::new(max_output_chars);
+        for chunk in &chunks {
+            converter.push(chunk);
+        }
+        converter.finish()
+    };
+
+    let (text, capped_peak) = peak_heap(|| stream(20_000));
+    assert!(text.starts_with("Lorem ipsum dolor sit amet & more."));
+    assert_eq!(text.chars().count(), 20_000);
+    assert!(capped_peak < OVERHEAD, "peak {capped_peak} bytes");
+    assert!(capped_peak * 8 < buffered_peak);
+
+    // Uncapped, memory follows the output rather than the input.
+    let (text, uncapped_peak) = peak_heap(|| stream(usize::MAX));
+    assert!(text.ends_with("Lorem ipsum dolor sit amet & more."));
+    assert!(
+        uncapped_peak < 2 * text.len() + OVERHEAD,
+        "peak {uncapped_peak} bytes for {} bytes of text",
+        text.len()
+    );
+}